@@ -23,7 +23,7 @@ fn push_front_dlist(b: &mut Bencher)
     b.iter(|| {
         let mut dl = LinkedList::new();
         let n = 1000;
-        for _ in (0..n) {
+        for _ in 0..n {
             dl.push_front(black_box(1));
         }
         dl
@@ -35,7 +35,7 @@ fn push_front_ringbuf(b: &mut Bencher)
     b.iter(|| {
         let mut l = VecDeque::new();
         let n = 1000;
-        for _ in (0..n) {
+        for _ in 0..n {
             l.push_front(black_box(1));
         }
         l
@@ -50,7 +50,7 @@ fn push_front_ringbuf_cap(b: &mut test::Bencher)
     b.iter(|| {
         let N = 1000;
         let mut l = VecDeque::with_capacity(N);
-        for _ in (0..N) {
+        for _ in 0..N {
             l.push_front(black_box(1));
         }
         l
@@ -63,7 +63,7 @@ fn push_front_list(b: &mut Bencher)
     b.iter(|| {
         let mut l = List::new();
         let n = 1000;
-        for _ in (0..n) {
+        for _ in 0..n {
             l.push_front(black_box(1));
         }
         l
@@ -75,7 +75,7 @@ fn push_front_list_cap(b: &mut Bencher)
     b.iter(|| {
         let n = 1000;
         let mut l = List::with_capacity(n);
-        for _ in (0..n) {
+        for _ in 0..n {
             l.push_front(black_box(1));
         }
         l
@@ -87,7 +87,7 @@ fn iterate_dlist(b: &mut Bencher)
     let mut dl = LinkedList::new();
     let n = 1000;
     let mut rng = repro_rng();
-    for _ in (0..n) {
+    for _ in 0..n {
         if rng.gen() {
             dl.push_front(black_box(1));
         } else {
@@ -107,7 +107,7 @@ fn iterate_ringbuf(b: &mut Bencher)
     let n = 1000;
     let mut rng = repro_rng();
     // scramble a bit so we get a random access iteration
-    for _ in (0..n) {
+    for _ in 0..n {
         if rng.gen() {
             dl.push_front(black_box(1));
         } else {
@@ -128,7 +128,7 @@ fn iterate_list(b: &mut Bencher)
     let n = 1000;
     let mut rng = repro_rng();
     // scramble a bit so we get a random access iteration
-    for _ in (0..n) {
+    for _ in 0..n {
         if rng.gen() {
             dl.push_front(black_box(1));
         } else {
@@ -142,6 +142,26 @@ fn iterate_list(b: &mut Bencher)
     })
 }
 
+fn iterate_list_values(b: &mut Bencher)
+{
+    let mut dl = List::new();
+    let n = 1000;
+    let mut rng = repro_rng();
+    // scramble a bit so we get a random access iteration
+    for _ in 0..n {
+        if rng.gen() {
+            dl.push_front(black_box(1));
+        } else {
+            dl.push_back(black_box(1));
+        }
+    }
+    b.iter(|| {
+        for elt in dl.values() {
+            black_box(elt);
+        }
+    })
+}
+
 benchmark_group!(benches,
                  push_front_dlist,
                  push_front_ringbuf,
@@ -149,5 +169,6 @@ benchmark_group!(benches,
                  push_front_list_cap,
                  iterate_dlist,
                  iterate_ringbuf,
-                 iterate_list);
+                 iterate_list,
+                 iterate_list_values);
 benchmark_main!(benches);
@@ -0,0 +1,150 @@
+/*
+ * C interface to ixlist, a doubly linked list stored in one contiguous
+ * allocation, of `void *` elements.
+ *
+ * Build the library with:
+ *
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ */
+
+#ifndef IXLIST_H
+#define IXLIST_H
+
+/*
+ * Generated from src/ffi.rs by cbindgen; do not edit. Regenerate with
+ * tests/c/check_header.sh --update.
+ */
+
+#include <stdbool.h>
+#include <stddef.h>
+
+/*
+ An opaque cursor into an **IxList**.
+ */
+typedef struct IxCursor IxCursor;
+
+/*
+ An opaque iterator of an **IxList**.
+ */
+typedef struct IxIter IxIter;
+
+/*
+ An opaque list of `void *`.
+ */
+typedef struct IxList IxList;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Create a new, empty list.
+ */
+IxList *ixlist_new(void);
+
+/*
+ Free **list**; the elements are not touched.
+ */
+void ixlist_free(IxList *list);
+
+/*
+ Return the number of elements in **list**.
+ */
+size_t ixlist_len(const IxList *list);
+
+/*
+ Insert **value** at the beginning of **list**.
+ */
+bool ixlist_push_front(IxList *list, void *value);
+
+/*
+ Insert **value** at the end of **list**.
+ */
+bool ixlist_push_back(IxList *list, void *value);
+
+/*
+ Remove the first element of **list** and write it to **out**.
+ */
+bool ixlist_pop_front(IxList *list, void **out);
+
+/*
+ Remove the last element of **list** and write it to **out**.
+ */
+bool ixlist_pop_back(IxList *list, void **out);
+
+/*
+ Create a new cursor, positioned at the head of **list**.
+ */
+IxCursor *ixlist_cursor_new(IxList *list);
+
+/*
+ Free **cursor**.
+ */
+void ixlist_cursor_free(IxCursor *cursor);
+
+/*
+ Return **true** if **cursor** is valid: it is at the end, or the
+ element at its position is still in the list.
+ */
+bool ixlist_cursor_valid(const IxCursor *cursor);
+
+/*
+ Write the element at the current position of **cursor** to **out**;
+ return **false** if the cursor is at the end.
+ */
+bool ixlist_cursor_current(IxCursor *cursor, void **out);
+
+/*
+ Step **cursor** forward, writing the element at the position it leaves
+ to **out**, like *Cursor::next()*. From the end, the cursor moves to the
+ first element and returns **false**.
+ */
+bool ixlist_cursor_next(IxCursor *cursor, void **out);
+
+/*
+ Step **cursor** backward, writing the element at the position it
+ arrives at to **out**, like *Cursor::prev()*. From the first element,
+ the cursor moves to the end and returns **false**.
+ */
+bool ixlist_cursor_prev(IxCursor *cursor, void **out);
+
+/*
+ Insert **value** at the current position of **cursor**, before the
+ current element, and move the cursor to it.
+ */
+bool ixlist_cursor_insert(IxCursor *cursor, void *value);
+
+/*
+ Remove the element at the current position of **cursor** and write it
+ to **out**; the cursor moves to the element after it. Return **false**
+ if the cursor is at the end.
+ */
+bool ixlist_cursor_remove(IxCursor *cursor, void **out);
+
+/*
+ Create an iterator of the elements of **list**, in order.
+ */
+IxIter *ixlist_iter_new(const IxList *list);
+
+/*
+ Write the next element of **iter** to **out**; return **false** at the
+ end.
+ */
+bool ixlist_iter_next(IxIter *iter, void **out);
+
+/*
+ Write the next element from the back of **iter** to **out**; return
+ **false** at the end.
+ */
+bool ixlist_iter_next_back(IxIter *iter, void **out);
+
+/*
+ Free **iter**.
+ */
+void ixlist_iter_free(IxIter *iter);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* IXLIST_H */
@@ -0,0 +1,538 @@
+//! **ArrayList** is a doubly linked list stored in an inline array.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem::MaybeUninit;
+use core::ptr;
+
+use Terminal;
+
+mod private {
+    pub trait Sealed { }
+}
+
+/// The integer type of the links of an **ArrayList**.
+///
+/// The largest value of the type is the "null" link, so the capacity of the
+/// list must be less than or equal to it.
+pub trait IndexType : Copy + Eq + fmt::Debug + private::Sealed
+{
+    /// The "null" link, as a usize.
+    #[doc(hidden)]
+    const END: usize;
+    #[doc(hidden)]
+    fn new(index: usize) -> Self;
+    #[doc(hidden)]
+    fn index(self) -> usize;
+}
+
+macro_rules! impl_index_type {
+    ($($t:ident)*) => {$(
+        impl private::Sealed for $t { }
+
+        impl IndexType for $t
+        {
+            const END: usize = $t::MAX as usize;
+            #[inline]
+            fn new(index: usize) -> Self { index as $t }
+            #[inline]
+            fn index(self) -> usize { self as usize }
+        }
+    )*}
+}
+
+impl_index_type!(u8 u16 u32 usize);
+
+struct Node<T, Ix>
+{
+    link: [Ix; 2],
+    value: T,
+}
+
+/// **ArrayList** is a doubly linked list with room for **N** elements,
+/// stored inline. It never allocates.
+///
+/// The links between elements are of type **Ix**, which can be *u8*, *u16*
+/// (the default), *u32* or *usize*. Pick the smallest type that can index
+/// **N** elements; a capacity that is too large for **Ix** is a compile
+/// time error.
+///
+/// Like **List**, the elements are kept at the start of the array: removing
+/// an element moves the last one into its place.
+pub struct ArrayList<T, const N: usize, Ix: IndexType = u16>
+{
+    /// Head, Tail
+    link: [Ix; 2],
+    len: usize,
+    nodes: [MaybeUninit<Node<T, Ix>>; N],
+}
+
+/// An iterator of the elements of an **ArrayList**.
+pub struct Iter<'a, T: 'a, const N: usize, Ix: IndexType + 'a = u16>
+{
+    link: [Ix; 2],
+    list: &'a ArrayList<T, N, Ix>,
+    /// Number of elements left
+    len: usize,
+}
+
+/// An iterator of mutable references to the elements of an **ArrayList**.
+pub struct IterMut<'a, T: 'a, const N: usize, Ix: IndexType + 'a = u16>
+{
+    link: [Ix; 2],
+    list: &'a mut ArrayList<T, N, Ix>,
+    /// Number of elements left
+    len: usize,
+}
+
+/// A cursor into an **ArrayList**, that works like the cursor of **List**.
+///
+/// The cursor is either positioned at an element or at the end of the list,
+/// and it wraps around between the end and the first element.
+pub struct Cursor<'a, T: 'a, const N: usize, Ix: IndexType + 'a = u16>
+{
+    pos: Ix,
+    list: &'a mut ArrayList<T, N, Ix>,
+}
+
+impl<T, const N: usize, Ix: IndexType> ArrayList<T, N, Ix>
+{
+    const CAPACITY_FITS: () = assert!(N <= Ix::END,
+                                      "ArrayList: capacity is too large for the index type");
+
+    /// Create a new, empty **ArrayList**.
+    pub fn new() -> Self
+    {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CAPACITY_FITS;
+        ArrayList {
+            link: [Self::end(); 2],
+            len: 0,
+            // An array of uninitialized values needs no initialization.
+            nodes: unsafe { MaybeUninit::uninit().assume_init() },
+        }
+    }
+
+    #[inline]
+    fn end() -> Ix { Ix::new(Ix::END) }
+
+    /// Return the number of elements in the ArrayList.
+    pub fn len(&self) -> usize { self.len }
+
+    /// Return **true** if the ArrayList has no elements.
+    pub fn is_empty(&self) -> bool { self.len == 0 }
+
+    /// Return the number of elements the ArrayList can hold, **N**.
+    pub fn capacity(&self) -> usize { N }
+
+    /// Return **true** if the ArrayList has no room for more elements.
+    pub fn is_full(&self) -> bool { self.len == N }
+
+    fn head(&self) -> Ix { self.link[0] }
+
+    fn node(&self, index: Ix) -> Option<&Node<T, Ix>>
+    {
+        let index = index.index();
+        if index < self.len {
+            // Nodes below len are initialized.
+            Some(unsafe { &*self.nodes[index].as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    fn node_mut(&mut self, index: Ix) -> Option<&mut Node<T, Ix>>
+    {
+        let index = index.index();
+        if index < self.len {
+            // Nodes below len are initialized.
+            Some(unsafe { &mut *self.nodes[index].as_mut_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Return an iterator of the elements, in order.
+    pub fn iter(&self) -> Iter<'_, T, N, Ix>
+    {
+        Iter {
+            link: self.link,
+            len: self.len,
+            list: self,
+        }
+    }
+
+    /// Return an iterator of mutable references to the elements, in order.
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, N, Ix>
+    {
+        IterMut {
+            link: self.link,
+            len: self.len,
+            list: self,
+        }
+    }
+
+    /// Return a new cursor, focused before the head of the ArrayList.
+    pub fn cursor(&mut self) -> Cursor<'_, T, N, Ix>
+    {
+        Cursor {
+            pos: self.head(),
+            list: self,
+        }
+    }
+
+    /// Write **node** to the first free spot and return its index.
+    fn push_node(&mut self, node: Node<T, Ix>) -> Ix
+    {
+        debug_assert!(!self.is_full());
+        let index = self.len;
+        self.nodes[index] = MaybeUninit::new(node);
+        self.len += 1;
+        Ix::new(index)
+    }
+
+    fn try_push_terminal(&mut self, value: T, term: Terminal) -> Result<(), T>
+    {
+        if self.is_full() {
+            return Err(value);
+        }
+        let t = term.index();
+        let index = Ix::new(self.len);
+        let mut link = [Self::end(); 2];
+        link[1 - t] = self.link[t];
+        match self.node_mut(link[1 - t]) {
+            None => self.link[1 - t] = index, // List was empty
+            Some(n) => n.link[t] = index,
+        }
+        self.link[t] = index;
+        self.push_node(Node { link, value });
+        Ok(())
+    }
+
+    /// Insert an element at the beginning of the ArrayList.
+    ///
+    /// Return the element in **Err** if the ArrayList is full.
+    pub fn try_push_front(&mut self, value: T) -> Result<(), T>
+    {
+        self.try_push_terminal(value, Terminal::Head)
+    }
+
+    /// Insert an element at the end of the ArrayList.
+    ///
+    /// Return the element in **Err** if the ArrayList is full.
+    pub fn try_push_back(&mut self, value: T) -> Result<(), T>
+    {
+        self.try_push_terminal(value, Terminal::Tail)
+    }
+
+    /// Update the neighbors **link** (prev, next) of a node: point the next
+    /// link of prev to **to_index[1]** and the prev link of next to
+    /// **to_index[0]**, or head and tail if there is no such neighbor.
+    fn relink(&mut self, link: [Ix; 2], to_index: [Ix; 2])
+    {
+        match self.node_mut(link[0]) {
+            None => self.link[0] = to_index[1],
+            Some(n) => n.link[1] = to_index[1],
+        }
+        match self.node_mut(link[1]) {
+            None => self.link[1] = to_index[0],
+            Some(n) => n.link[0] = to_index[0],
+        }
+    }
+
+    /// Unlink and remove the node at **index**, moving the last node
+    /// into the free spot.
+    fn remove_index(&mut self, index: Ix) -> T
+    {
+        let link = self.node(index).expect("ArrayList: invalid index").link;
+        // unlink the node by pointing its neighbors to each other
+        self.relink(link, [link[0], link[1]]);
+        let last = Ix::new(self.len - 1);
+        // The node is moved out, and its spot is either overwritten
+        // or past the new length.
+        let removed = unsafe { ptr::read(self.nodes[index.index()].as_ptr()) };
+        if index != last {
+            let moved_link = self.node(last).unwrap().link;
+            self.relink(moved_link, [index, index]);
+            self.nodes[index.index()] = unsafe {
+                ptr::read(&self.nodes[last.index()])
+            };
+        }
+        self.len -= 1;
+        removed.value
+    }
+
+    fn pop_terminal(&mut self, term: Terminal) -> Option<T>
+    {
+        match self.node(self.link[term.index()]) {
+            None => None,
+            Some(_) => Some(self.remove_index(self.link[term.index()])),
+        }
+    }
+
+    /// Remove the element at the beginning of the ArrayList and return it,
+    /// or return **None** if the ArrayList is empty.
+    pub fn pop_front(&mut self) -> Option<T>
+    {
+        self.pop_terminal(Terminal::Head)
+    }
+
+    /// Remove the element at the end of the ArrayList and return it,
+    /// or return **None** if the ArrayList is empty.
+    pub fn pop_back(&mut self) -> Option<T>
+    {
+        self.pop_terminal(Terminal::Tail)
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self)
+    {
+        let len = self.len;
+        // Reset first, in case a destructor panics.
+        self.len = 0;
+        self.link = [Self::end(); 2];
+        for node in &mut self.nodes[..len] {
+            unsafe {
+                ptr::drop_in_place(node.as_mut_ptr());
+            }
+        }
+    }
+}
+
+impl<T, const N: usize, Ix: IndexType> Drop for ArrayList<T, N, Ix>
+{
+    fn drop(&mut self)
+    {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize, Ix: IndexType> Default for ArrayList<T, N, Ix>
+{
+    fn default() -> Self { ArrayList::new() }
+}
+
+impl<T: Clone, const N: usize, Ix: IndexType> Clone for ArrayList<T, N, Ix>
+{
+    /// Clone the ArrayList, keeping the layout of the elements.
+    fn clone(&self) -> Self
+    {
+        let mut list = ArrayList::new();
+        for index in 0..self.len {
+            let n = self.node(Ix::new(index)).unwrap();
+            list.push_node(Node { link: n.link, value: n.value.clone() });
+        }
+        list.link = self.link;
+        list
+    }
+}
+
+impl<T: fmt::Debug, const N: usize, Ix: IndexType> fmt::Debug for ArrayList<T, N, Ix>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> IntoIterator for &'a ArrayList<T, N, Ix>
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, N, Ix>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> IntoIterator for &'a mut ArrayList<T, N, Ix>
+{
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T, N, Ix>;
+    fn into_iter(self) -> Self::IntoIter { self.iter_mut() }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> Iter<'a, T, N, Ix>
+{
+    /// Step the iterator from the head or tail
+    fn next_terminal(&mut self, term: Terminal) -> Option<&'a T>
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let h = term.index();
+        let t = term.opposite().index();
+        let n = self.list.node(self.link[h]).unwrap();
+        self.len -= 1;
+        self.link[h] = n.link[t];
+        Some(&n.value)
+    }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> Clone for Iter<'a, T, N, Ix>
+{
+    fn clone(&self) -> Self { Iter { ..*self } }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> Iterator for Iter<'a, T, N, Ix>
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> { self.next_terminal(Terminal::Head) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.len, Some(self.len)) }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> DoubleEndedIterator for Iter<'a, T, N, Ix>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> { self.next_terminal(Terminal::Tail) }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> ExactSizeIterator for Iter<'a, T, N, Ix> { }
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> FusedIterator for Iter<'a, T, N, Ix> { }
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> IterMut<'a, T, N, Ix>
+{
+    /// Step the iterator from the head or tail
+    fn next_terminal(&mut self, term: Terminal) -> Option<&'a mut T>
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let h = term.index();
+        let t = term.opposite().index();
+        let n = self.list.node_mut(self.link[h]).unwrap();
+        self.len -= 1;
+        self.link[h] = n.link[t];
+        // Each element is visited only once, so we can extend the life
+        // of the reference, like in List's IterMut.
+        Some(unsafe { &mut *(&mut n.value as *mut _) })
+    }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> Iterator for IterMut<'a, T, N, Ix>
+{
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> { self.next_terminal(Terminal::Head) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.len, Some(self.len)) }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> DoubleEndedIterator for IterMut<'a, T, N, Ix>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> { self.next_terminal(Terminal::Tail) }
+}
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> ExactSizeIterator for IterMut<'a, T, N, Ix> { }
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> FusedIterator for IterMut<'a, T, N, Ix> { }
+
+impl<'a, T: 'a, const N: usize, Ix: IndexType> Cursor<'a, T, N, Ix>
+{
+    /// Return the element at the current position, or **None** if the
+    /// cursor is at the end.
+    pub fn current(&mut self) -> Option<&mut T>
+    {
+        self.list.node_mut(self.pos).map(|n| &mut n.value)
+    }
+
+    /// Move the cursor to the next position, wrapping around from the end
+    /// to the first element.
+    ///
+    /// Return **true** if the cursor moved, which is unless the list is
+    /// empty.
+    pub fn move_next(&mut self) -> bool
+    {
+        if self.list.is_empty() {
+            return false;
+        }
+        self.pos = match self.list.node(self.pos) {
+            None => self.list.head(),
+            Some(n) => n.link[1],
+        };
+        true
+    }
+
+    /// Move the cursor to the previous position, wrapping around from the
+    /// first element to the end.
+    ///
+    /// Return **true** if the cursor moved, which is unless the list is
+    /// empty.
+    pub fn move_prev(&mut self) -> bool
+    {
+        if self.list.is_empty() {
+            return false;
+        }
+        self.pos = if self.pos == self.list.head() {
+            ArrayList::<T, N, Ix>::end()
+        } else {
+            match self.list.node(self.pos) {
+                None => self.list.link[1],
+                Some(n) => n.link[0],
+            }
+        };
+        true
+    }
+
+    /// Step the cursor forward, returning the element at the position it
+    /// leaves, or **None** at the end.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&mut T>
+    {
+        let pos = self.pos;
+        self.move_next();
+        self.list.node_mut(pos).map(|n| &mut n.value)
+    }
+
+    /// Step the cursor backward, returning the element at the position it
+    /// arrives at, or **None** when stepping back from the first element
+    /// to the end.
+    pub fn prev(&mut self) -> Option<&mut T>
+    {
+        if self.pos == self.list.head() {
+            self.move_prev();
+            return None;
+        }
+        self.move_prev();
+        self.current()
+    }
+
+    /// Insert an element at the current position, e.g. before the element
+    /// that would be returned by *.next()* in this position. The cursor
+    /// moves to the inserted element, just like *Cursor::insert* of **List**.
+    ///
+    /// Return the element in **Err** if the ArrayList is full.
+    pub fn try_insert(&mut self, value: T) -> Result<(), T>
+    {
+        if self.list.is_full() {
+            return Err(value);
+        }
+        let index = Ix::new(self.list.len());
+        let prev = match self.list.node(self.pos) {
+            None => self.list.link[1],
+            Some(n) => n.link[0],
+        };
+        self.list.relink([prev, self.pos], [index, index]);
+        self.list.push_node(Node { link: [prev, self.pos], value });
+        self.pos = index;
+        Ok(())
+    }
+
+    /// Remove the element at the current position and return it, or return
+    /// **None** if the cursor is at the end. The cursor moves to the next
+    /// position.
+    pub fn remove(&mut self) -> Option<T>
+    {
+        let mut next = self.list.node(self.pos)?.link[1];
+        if next.index() == self.list.len() - 1 {
+            // the last node moves into the removed node's spot
+            next = self.pos;
+        }
+        let value = self.list.remove_index(self.pos);
+        self.pos = next;
+        Some(value)
+    }
+}
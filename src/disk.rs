@@ -0,0 +1,556 @@
+//! **DiskList** is a **List** of **Plain** values that is kept in files,
+//! for example a persistent work queue.
+//!
+//! The list lives in a directory, with two files:
+//!
+//! - `snapshot`: the list as of some operation, with the node array and
+//!   head and tail links in the layout of a **RawList** with *u64* links.
+//! - `log`: a write-ahead log of the operations since the snapshot, after a
+//!   header with the size of the element type. Each operation is appended
+//!   and synced to the log before it is applied, so once a method returns
+//!   **Ok**, the change survives a crash.
+//!
+//! When the log grows long, the list writes a new snapshot (to a temporary
+//! file that is renamed into place) and empties the log.
+//!
+//! Opening the list reads the snapshot and replays the log. A record at the
+//! end of the log that was only partly written when the process crashed is
+//! discarded, so the list recovers to its state after the last complete
+//! operation. Every operation has a sequence number, so records that are
+//! already in the snapshot are skipped. Any other damage is an error, and
+//! the files are left as they are.
+//!
+//! The node array is replayed exactly, so the list has the same node order
+//! after recovery. Values are stored in native byte order; the framing of
+//! the files is little endian.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::mem::{size_of, size_of_val};
+use std::path::{Path, PathBuf};
+use std::ptr;
+use std::slice;
+use std::vec::Vec;
+
+use raw::{Plain, RawList};
+use {Iter, List, Node, Slot, END};
+
+const SNAPSHOT: &str = "snapshot";
+const SNAPSHOT_TMP: &str = "snapshot.tmp";
+const LOG: &str = "log";
+const SNAPSHOT_MAGIC: &[u8; 8] = b"ixlsnap1";
+const LOG_MAGIC: &[u8; 8] = b"ixllog01";
+const LOG_HEADER_LEN: usize = 16;
+
+/// Write a new snapshot when the log has this many records.
+const CHECKPOINT_RECORDS: u64 = 1024;
+
+/// The null link of the snapshot nodes.
+const RAW_END: u64 = u64::MAX;
+
+/// An operation, as recorded in the log.
+#[derive(Copy, Clone, Debug)]
+enum Op<T>
+{
+    PushBack(T),
+    PushFront(T),
+    PopFront,
+    PopBack,
+    /// Insert before the node at the index, or at the end.
+    Insert(usize, T),
+    /// Remove the node at the index.
+    Remove(usize),
+}
+
+impl<T: Plain> Op<T>
+{
+    fn encode(&self, out: &mut Vec<u8>)
+    {
+        match *self {
+            Op::PushBack(ref v) => { out.push(0); out.extend_from_slice(value_bytes(v)); }
+            Op::PushFront(ref v) => { out.push(1); out.extend_from_slice(value_bytes(v)); }
+            Op::PopFront => out.push(2),
+            Op::PopBack => out.push(3),
+            Op::Insert(index, ref v) => {
+                out.push(4);
+                out.extend_from_slice(&raw_link(index).to_le_bytes());
+                out.extend_from_slice(value_bytes(v));
+            }
+            Op::Remove(index) => {
+                out.push(5);
+                out.extend_from_slice(&raw_link(index).to_le_bytes());
+            }
+        }
+    }
+
+    fn decode(data: &[u8]) -> Option<Self>
+    {
+        let (&tag, rest) = data.split_first()?;
+        let (index, value) = match tag {
+            4 | 5 if rest.len() >= 8 => {
+                let (index, value) = rest.split_at(8);
+                (Some(list_link(read_u64(index))), value)
+            }
+            _ => (None, rest),
+        };
+        let op = match (tag, index) {
+            (0, None) => Op::PushBack(read_value(value)?),
+            (1, None) => Op::PushFront(read_value(value)?),
+            (2, None) if value.is_empty() => Op::PopFront,
+            (3, None) if value.is_empty() => Op::PopBack,
+            (4, Some(i)) => Op::Insert(i, read_value(value)?),
+            (5, Some(i)) if value.is_empty() => Op::Remove(i),
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Apply the operation to **list**, and return the position of the
+    /// cursor after it, or **None** if the operation is not valid.
+    fn apply(self, list: &mut List<T>) -> Option<usize>
+    {
+        match self {
+            Op::PushBack(v) => list.push_back(v),
+            Op::PushFront(v) => list.push_front(v),
+            Op::PopFront => { list.pop_front()?; }
+            Op::PopBack => { list.pop_back()?; }
+            Op::Insert(index, v) => {
+                if index != END && index >= list.len() {
+                    return None;
+                }
+                let mut c = list.cursor_at_index(index);
+                c.insert(v);
+                return Some(c.pos);
+            }
+            Op::Remove(index) => {
+                if index >= list.len() {
+                    return None;
+                }
+                let mut c = list.cursor_at_index(index);
+                c.remove_n(1);
+                return Some(c.pos);
+            }
+        }
+        Some(END)
+    }
+}
+
+fn value_bytes<T: Plain>(value: &T) -> &[u8]
+{
+    // Plain values have no padding bytes.
+    unsafe { slice::from_raw_parts(value as *const T as *const u8, size_of::<T>()) }
+}
+
+fn read_value<T: Plain>(data: &[u8]) -> Option<T>
+{
+    if data.len() != size_of::<T>() {
+        return None;
+    }
+    // Any bytes are a valid Plain value.
+    unsafe { Some(ptr::read_unaligned(data.as_ptr() as *const T)) }
+}
+
+fn read_u64(data: &[u8]) -> u64
+{
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&data[..8]);
+    u64::from_le_bytes(bytes)
+}
+
+fn raw_link(index: usize) -> u64
+{
+    if index == END { RAW_END } else { index as u64 }
+}
+
+fn list_link(index: u64) -> usize
+{
+    if index == RAW_END { END } else { index as usize }
+}
+
+/// FNV-1a, to detect torn or damaged records.
+fn checksum(data: &[u8]) -> u64
+{
+    data.iter().fold(0xcbf29ce484222325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn invalid_data(msg: &str) -> io::Error
+{
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// A buffer of **len** zeroed bytes, aligned for a **RawList**.
+fn raw_buffer(len: usize) -> Vec<u128>
+{
+    std::vec![0; len.div_ceil(size_of::<u128>())]
+}
+
+fn raw_bytes(buf: &mut [u128], len: usize) -> &mut [u8]
+{
+    assert!(len <= size_of_val(buf));
+    unsafe { slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut u8, len) }
+}
+
+/// **DiskList** is a **List** of **Plain** values that is kept in a
+/// directory, with a write-ahead log, and recovers to a consistent state
+/// after a crash.
+///
+/// Changes are written to disk before they are applied, so each method that
+/// changes the list returns an `io::Result`. If writing fails, the list is
+/// unchanged.
+pub struct DiskList<T: Plain>
+{
+    list: List<T>,
+    dir: PathBuf,
+    log: File,
+    /// Sequence number of the last operation
+    seq: u64,
+    /// Length in bytes of the header and the complete records of the log
+    log_len: u64,
+    /// Number of records in the log
+    log_records: u64,
+    /// Reused buffer for encoding records
+    buf: Vec<u8>,
+}
+
+/// A cursor into a **DiskList**, that can insert and remove elements
+/// anywhere in O(1) time.
+///
+/// Like the cursor of a **List**, it has a position at each element and one
+/// at the end, and moving wraps around between the end and the first
+/// element.
+pub struct DiskCursor<'a, T: Plain + 'a>
+{
+    pos: usize,
+    list: &'a mut DiskList<T>,
+}
+
+impl<T: Plain> DiskList<T>
+{
+    /// Open the **DiskList** in the directory **dir**, creating the
+    /// directory and an empty list if they do not exist.
+    ///
+    /// Return an error of kind `InvalidData` if the snapshot or the log is
+    /// damaged or holds a list of another element type. The files are not
+    /// changed then.
+    pub fn open<P: AsRef<Path>>(dir: P) -> io::Result<Self>
+    {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let (mut list, mut seq) = match File::open(dir.join(SNAPSHOT)) {
+            Ok(mut f) => {
+                let mut data = Vec::new();
+                f.read_to_end(&mut data)?;
+                read_snapshot(&data)?
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => (List::new(), 0),
+            Err(e) => return Err(e),
+        };
+
+        let mut log = OpenOptions::new().read(true).append(true).create(true)
+                                        .open(dir.join(LOG))?;
+        let mut data = Vec::new();
+        log.read_to_end(&mut data)?;
+        let header = log_header::<T>();
+        if data.len() < LOG_HEADER_LEN {
+            // A new log, or one whose header was torn
+            if !header.starts_with(&data) {
+                return Err(invalid_data("DiskList: the log is damaged"));
+            }
+            log.set_len(0)?;
+            log.write_all(&header)?;
+            log.sync_all()?;
+            data = header.to_vec();
+        } else if data[..LOG_HEADER_LEN] != header {
+            return Err(invalid_data("DiskList: the log is damaged or has another element type"));
+        }
+
+        let mut log_len = LOG_HEADER_LEN;
+        let mut log_records = 0;
+        while let Some((rec_seq, op, rec_len)) = read_record::<T>(&data[log_len..])? {
+            if rec_seq > seq + 1 {
+                return Err(invalid_data("DiskList: a log record is missing"));
+            }
+            if rec_seq == seq + 1 {
+                op.apply(&mut list).ok_or_else(|| invalid_data("DiskList: invalid log record"))?;
+                seq = rec_seq;
+            }
+            log_len += rec_len;
+            log_records += 1;
+        }
+        // Drop a torn record at the end, so that new records follow the
+        // complete ones.
+        if log_len < data.len() {
+            log.set_len(log_len as u64)?;
+            log.sync_all()?;
+        }
+
+        Ok(DiskList {
+            list,
+            dir,
+            log,
+            seq,
+            log_len: log_len as u64,
+            log_records,
+            buf: Vec::new(),
+        })
+    }
+
+    /// Return the number of elements in the list.
+    pub fn len(&self) -> usize { self.list.len() }
+
+    /// Return **true** if the list has no elements.
+    pub fn is_empty(&self) -> bool { self.list.is_empty() }
+
+    /// Return an iterator of the elements, in order.
+    pub fn iter(&self) -> Iter<'_, T> { self.list.iter() }
+
+    /// Return a new cursor, positioned at the head of the list.
+    pub fn cursor(&mut self) -> DiskCursor<'_, T>
+    {
+        DiskCursor { pos: self.list.head(), list: self }
+    }
+
+    /// Insert an element at the end of the list.
+    pub fn push_back(&mut self, value: T) -> io::Result<()>
+    {
+        self.run(Op::PushBack(value)).map(|_| ())
+    }
+
+    /// Insert an element at the beginning of the list.
+    pub fn push_front(&mut self, value: T) -> io::Result<()>
+    {
+        self.run(Op::PushFront(value)).map(|_| ())
+    }
+
+    /// Remove the element at the beginning of the list and return it,
+    /// or **None** if the list is empty.
+    pub fn pop_front(&mut self) -> io::Result<Option<T>>
+    {
+        self.pop(Op::PopFront, self.list.head())
+    }
+
+    /// Remove the element at the end of the list and return it,
+    /// or **None** if the list is empty.
+    pub fn pop_back(&mut self) -> io::Result<Option<T>>
+    {
+        self.pop(Op::PopBack, self.list.tail())
+    }
+
+    fn pop(&mut self, op: Op<T>, index: usize) -> io::Result<Option<T>>
+    {
+        let value = match self.list.nodes.get(index) {
+            None => return Ok(None),
+            Some(n) => n.value,
+        };
+        self.run(op)?;
+        Ok(Some(value))
+    }
+
+    /// Log **op**, then apply it and return the cursor position after it.
+    fn run(&mut self, op: Op<T>) -> io::Result<usize>
+    {
+        if self.log_records >= CHECKPOINT_RECORDS {
+            self.checkpoint()?;
+        }
+        self.buf.clear();
+        self.buf.extend_from_slice(&[0; 4]);
+        self.buf.extend_from_slice(&(self.seq + 1).to_le_bytes());
+        op.encode(&mut self.buf);
+        let body_len = self.buf.len() - 4;
+        self.buf[..4].copy_from_slice(&(body_len as u32).to_le_bytes());
+        let sum = checksum(&self.buf[4..]);
+        self.buf.extend_from_slice(&sum.to_le_bytes());
+
+        if let Err(e) = self.log.write_all(&self.buf).and_then(|_| self.log.sync_data()) {
+            // Don't leave a partial record for the next one to follow.
+            let _ = self.log.set_len(self.log_len);
+            return Err(e);
+        }
+        self.seq += 1;
+        self.log_len += self.buf.len() as u64;
+        self.log_records += 1;
+        Ok(op.apply(&mut self.list).expect("DiskList: operation is valid"))
+    }
+
+    /// Write a snapshot of the list and empty the log.
+    ///
+    /// This happens automatically when the log grows long.
+    pub fn checkpoint(&mut self) -> io::Result<()>
+    {
+        let len = self.list.len();
+        let raw_len = RawList::<T, u64>::size_for(len);
+        let mut buf = raw_buffer(raw_len);
+        {
+            let bytes = raw_bytes(&mut buf, raw_len);
+            let mut raw = RawList::<T, u64>::init(bytes)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            let nodes = self.list.nodes.iter().map(|n| {
+                ([raw_link(n.prev()), raw_link(n.next())], n.value)
+            });
+            raw.set_parts([raw_link(self.list.head()), raw_link(self.list.tail())], nodes);
+        }
+        let bytes = raw_bytes(&mut buf, raw_len);
+
+        let tmp = self.dir.join(SNAPSHOT_TMP);
+        {
+            let mut f = File::create(&tmp)?;
+            f.write_all(SNAPSHOT_MAGIC)?;
+            f.write_all(&self.seq.to_le_bytes())?;
+            f.write_all(&(raw_len as u64).to_le_bytes())?;
+            f.write_all(&checksum(bytes).to_le_bytes())?;
+            f.write_all(bytes)?;
+            f.sync_all()?;
+        }
+        fs::rename(&tmp, self.dir.join(SNAPSHOT))?;
+        sync_dir(&self.dir)?;
+
+        // A crash before this point leaves log records that are already in
+        // the snapshot; their sequence numbers tell recovery to skip them.
+        self.log.set_len(LOG_HEADER_LEN as u64)?;
+        self.log.sync_all()?;
+        self.log_len = LOG_HEADER_LEN as u64;
+        self.log_records = 0;
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()>
+{
+    File::open(dir)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> { Ok(()) }
+
+/// Parse a snapshot into the list and its sequence number.
+fn read_snapshot<T: Plain>(data: &[u8]) -> io::Result<(List<T>, u64)>
+{
+    let header_len = SNAPSHOT_MAGIC.len() + 3 * 8;
+    if data.len() < header_len || &data[..8] != SNAPSHOT_MAGIC {
+        return Err(invalid_data("DiskList: the snapshot is damaged"));
+    }
+    let seq = read_u64(&data[8..]);
+    let raw_len = read_u64(&data[16..]);
+    let sum = read_u64(&data[24..]);
+    let bytes = &data[header_len..];
+    if raw_len != bytes.len() as u64 || checksum(bytes) != sum {
+        return Err(invalid_data("DiskList: the snapshot is damaged"));
+    }
+
+    let mut buf = raw_buffer(bytes.len());
+    let copy = raw_bytes(&mut buf, bytes.len());
+    copy.copy_from_slice(bytes);
+    let raw = RawList::<T, u64>::attach(copy)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let (link, raw_nodes) = raw.parts();
+    let mut list = List::with_capacity(raw_nodes.len());
+    for (index, n) in raw_nodes.iter().enumerate() {
+        list.nodes.push(Node {
+            link: [list_link(n.link[0]), list_link(n.link[1])],
+            slot: index,
+            value: n.value,
+        });
+        list.slots.push(Slot { node: index, gen: 0 });
+    }
+    list.link = [list_link(link[0]), list_link(link[1])];
+    Ok((list, seq))
+}
+
+/// The header of the log, with the size of the element type.
+fn log_header<T>() -> [u8; LOG_HEADER_LEN]
+{
+    let mut header = [0; LOG_HEADER_LEN];
+    header[..8].copy_from_slice(LOG_MAGIC);
+    header[8..].copy_from_slice(&(size_of::<T>() as u64).to_le_bytes());
+    header
+}
+
+/// Parse the log record at the start of **data**, and return its sequence
+/// number, operation and length, or **None** if the record is incomplete,
+/// which is what a crash leaves at the end of the log.
+///
+/// Return an error if the record is complete but damaged.
+fn read_record<T: Plain>(data: &[u8]) -> io::Result<Option<(u64, Op<T>, usize)>>
+{
+    if data.len() < 4 {
+        return Ok(None);
+    }
+    let mut len = [0; 4];
+    len.copy_from_slice(&data[..4]);
+    let body_len = u32::from_le_bytes(len) as usize;
+    let rec_len = 4 + body_len + 8;
+    if data.len() < rec_len {
+        return Ok(None);
+    }
+    let body = &data[4..4 + body_len];
+    if body_len < 8 || checksum(body) != read_u64(&data[4 + body_len..]) {
+        return Err(invalid_data("DiskList: a log record is damaged"));
+    }
+    let op = Op::decode(&body[8..])
+        .ok_or_else(|| invalid_data("DiskList: a log record is damaged"))?;
+    Ok(Some((read_u64(body), op, rec_len)))
+}
+
+impl<'a, T: Plain + 'a> DiskCursor<'a, T>
+{
+    /// Return the element at the current position, or **None** if the
+    /// cursor is at the end.
+    pub fn current(&self) -> Option<&T>
+    {
+        self.list.list.nodes.get(self.pos).map(|n| &n.value)
+    }
+
+    /// Move the cursor to the next position.
+    ///
+    /// From the last element it moves to the end, and from the end to the
+    /// first element.
+    pub fn move_next(&mut self)
+    {
+        let list = &self.list.list;
+        self.pos = match list.nodes.get(self.pos) {
+            None => list.head(),
+            Some(n) => n.next(),
+        };
+    }
+
+    /// Move the cursor to the previous position.
+    ///
+    /// From the first element it moves to the end, and from the end to the
+    /// last element.
+    pub fn move_prev(&mut self)
+    {
+        let list = &self.list.list;
+        self.pos = if self.pos == list.head() {
+            END
+        } else {
+            match list.nodes.get(self.pos) {
+                None => list.tail(),
+                Some(n) => n.prev(),
+            }
+        };
+    }
+
+    /// Insert an element at the current position, e.g. before the current
+    /// element, and move the cursor to it.
+    pub fn insert(&mut self, value: T) -> io::Result<()>
+    {
+        self.pos = self.list.run(Op::Insert(self.pos, value))?;
+        Ok(())
+    }
+
+    /// Remove the element at the current position and return it, or
+    /// **None** if the cursor is at the end. The cursor moves to the
+    /// element after it.
+    pub fn remove(&mut self) -> io::Result<Option<T>>
+    {
+        let value = match self.current() {
+            None => return Ok(None),
+            Some(v) => *v,
+        };
+        self.pos = self.list.run(Op::Remove(self.pos))?;
+        Ok(Some(value))
+    }
+}
@@ -0,0 +1,252 @@
+//! A C interface to a **List** of `void *`, with the `ffi` crate feature.
+//!
+//! The declarations are in `include/ixlist.h`, which is generated from
+//! this module by cbindgen; see `tests/c/check_header.sh`. Build the shared
+//! library with:
+//!
+//! ```text
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! Functions that return an element take an `out` pointer, which may be
+//! null, and return **true** if there was an element; so a null element can
+//! be told apart from no element. Functions that insert return **false** if
+//! memory could not be allocated, and leave the list unchanged.
+//!
+//! # Safety
+//!
+//! Pointers to lists, cursors and iterators must come from this module and
+//! not be used after they are freed. Freeing a null pointer does nothing.
+//! A cursor or iterator must not outlive its list. A list must not be
+//! changed while an iterator of it is in use.
+//!
+//! A cursor keeps its position when the list is changed other than through
+//! it, unless the element at its position is removed. Then the cursor is
+//! invalid: the cursor functions return **false** and do nothing, and
+//! *ixlist_cursor_valid* returns **false**.
+
+#![allow(clippy::missing_safety_doc)]
+
+use core::ffi::c_void;
+use core::mem;
+use std::boxed::Box;
+
+use {Cursor, Handle, Iter, List, END};
+
+/// An opaque list of `void *`.
+pub struct IxList
+{
+    list: List<*mut c_void>,
+}
+
+/// An opaque cursor into an **IxList**.
+pub struct IxCursor
+{
+    list: *mut IxList,
+    /// The element at the cursor's position, or **None** at the end.
+    handle: Option<Handle>,
+}
+
+/// An opaque iterator of an **IxList**.
+pub struct IxIter
+{
+    iter: Iter<'static, *mut c_void>,
+}
+
+/// Write **value** to **out**, if it is not null, and return **true** if
+/// there was a value.
+unsafe fn put(out: *mut *mut c_void, value: Option<*mut c_void>) -> bool
+{
+    match value {
+        None => false,
+        Some(v) => {
+            if !out.is_null() {
+                *out = v;
+            }
+            true
+        }
+    }
+}
+
+/// Create a new, empty list.
+#[no_mangle]
+pub extern "C" fn ixlist_new() -> *mut IxList
+{
+    Box::into_raw(Box::new(IxList { list: List::new() }))
+}
+
+/// Free **list**; the elements are not touched.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_free(list: *mut IxList)
+{
+    if !list.is_null() {
+        drop(Box::from_raw(list));
+    }
+}
+
+/// Return the number of elements in **list**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_len(list: *const IxList) -> usize
+{
+    (*list).list.len()
+}
+
+/// Insert **value** at the beginning of **list**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_push_front(list: *mut IxList, value: *mut c_void) -> bool
+{
+    (*list).list.try_push_front(value).is_ok()
+}
+
+/// Insert **value** at the end of **list**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_push_back(list: *mut IxList, value: *mut c_void) -> bool
+{
+    (*list).list.try_push_back(value).is_ok()
+}
+
+/// Remove the first element of **list** and write it to **out**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_pop_front(list: *mut IxList, out: *mut *mut c_void) -> bool
+{
+    put(out, (*list).list.pop_front())
+}
+
+/// Remove the last element of **list** and write it to **out**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_pop_back(list: *mut IxList, out: *mut *mut c_void) -> bool
+{
+    put(out, (*list).list.pop_back())
+}
+
+/// Create a new cursor, positioned at the head of **list**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_cursor_new(list: *mut IxList) -> *mut IxCursor
+{
+    let handle = (*list).list.handles().next();
+    Box::into_raw(Box::new(IxCursor { list, handle }))
+}
+
+/// Free **cursor**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_cursor_free(cursor: *mut IxCursor)
+{
+    if !cursor.is_null() {
+        drop(Box::from_raw(cursor));
+    }
+}
+
+/// Return **true** if **cursor** is valid: it is at the end, or the
+/// element at its position is still in the list.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_cursor_valid(cursor: *const IxCursor) -> bool
+{
+    let cursor = &*cursor;
+    match cursor.handle {
+        None => true,
+        Some(handle) => (*cursor.list).list.handle_index(handle).is_some(),
+    }
+}
+
+/// Run **f** with a **Cursor** at the position of **cursor**, and keep the
+/// position it moves to. Return **None**, without calling **f**, if the
+/// cursor is invalid.
+unsafe fn with_cursor<F, R>(cursor: *mut IxCursor, f: F) -> Option<R>
+    where F: FnOnce(&mut Cursor<'_, *mut c_void>) -> R
+{
+    let cursor = &mut *cursor;
+    let list = &mut (*cursor.list).list;
+    let mut c = match cursor.handle {
+        None => list.cursor_at_index(END),
+        Some(handle) => list.cursor_at(handle)?,
+    };
+    let result = f(&mut c);
+    cursor.handle = c.handle();
+    Some(result)
+}
+
+/// Write the element at the current position of **cursor** to **out**;
+/// return **false** if the cursor is at the end.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_cursor_current(cursor: *mut IxCursor, out: *mut *mut c_void)
+    -> bool
+{
+    put(out, with_cursor(cursor, |c| c.current().cloned()).flatten())
+}
+
+/// Step **cursor** forward, writing the element at the position it leaves
+/// to **out**, like *Cursor::next()*. From the end, the cursor moves to the
+/// first element and returns **false**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_cursor_next(cursor: *mut IxCursor, out: *mut *mut c_void)
+    -> bool
+{
+    put(out, with_cursor(cursor, |c| c.next().cloned()).flatten())
+}
+
+/// Step **cursor** backward, writing the element at the position it
+/// arrives at to **out**, like *Cursor::prev()*. From the first element,
+/// the cursor moves to the end and returns **false**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_cursor_prev(cursor: *mut IxCursor, out: *mut *mut c_void)
+    -> bool
+{
+    put(out, with_cursor(cursor, |c| c.prev().cloned()).flatten())
+}
+
+/// Insert **value** at the current position of **cursor**, before the
+/// current element, and move the cursor to it.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_cursor_insert(cursor: *mut IxCursor, value: *mut c_void) -> bool
+{
+    with_cursor(cursor, |c| c.try_insert(value).is_ok()) == Some(true)
+}
+
+/// Remove the element at the current position of **cursor** and write it
+/// to **out**; the cursor moves to the element after it. Return **false**
+/// if the cursor is at the end.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_cursor_remove(cursor: *mut IxCursor, out: *mut *mut c_void)
+    -> bool
+{
+    let value = with_cursor(cursor, |c| {
+        let value = c.current().cloned();
+        c.remove_n(1);
+        value
+    });
+    put(out, value.flatten())
+}
+
+/// Create an iterator of the elements of **list**, in order.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_iter_new(list: *const IxList) -> *mut IxIter
+{
+    // The caller keeps the list alive and unchanged while iterating.
+    let iter = mem::transmute::<Iter<'_, *mut c_void>, Iter<'static, *mut c_void>>((*list).list.iter());
+    Box::into_raw(Box::new(IxIter { iter }))
+}
+
+/// Write the next element of **iter** to **out**; return **false** at the
+/// end.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_iter_next(iter: *mut IxIter, out: *mut *mut c_void) -> bool
+{
+    put(out, (*iter).iter.next().cloned())
+}
+
+/// Write the next element from the back of **iter** to **out**; return
+/// **false** at the end.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_iter_next_back(iter: *mut IxIter, out: *mut *mut c_void) -> bool
+{
+    put(out, (*iter).iter.next_back().cloned())
+}
+
+/// Free **iter**.
+#[no_mangle]
+pub unsafe extern "C" fn ixlist_iter_free(iter: *mut IxIter)
+{
+    if !iter.is_null() {
+        drop(Box::from_raw(iter));
+    }
+}
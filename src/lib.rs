@@ -1,18 +1,72 @@
 //! [`List`] is a doubly linked list stored in one contiguous allocation.
 //!
 //! [`List`]: struct.List.html
+//!
+//! ## Crate features
+//!
+//! - **std** (default): use the standard library, and provide **DiskList**.
+//!   Without it, the crate is `no_std` and only needs `alloc`.
+//! - **serde**: serialize and deserialize a **List**, as a sequence, or
+//!   with its layout using **ListLayout**.
+//! - **ffi**: a C interface to a **List** of `void *`, in the **ffi**
+//!   module.
+//! - **allocator_api**: let a **List** keep its nodes in a `Vec` with a
+//!   custom allocator. Requires nightly Rust.
+
+#![no_std]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
+
+#[cfg(feature = "std")]
+extern crate std;
+extern crate alloc;
+#[cfg(feature = "serde")]
+extern crate serde;
 
-use std::iter::IntoIterator;
-use std::iter::FromIterator;
+use core::iter::IntoIterator;
+use core::iter::FromIterator;
+use core::iter::{Chain, FusedIterator, Rev};
+use core::marker::PhantomData;
+use core::array;
+use core::convert::Infallible;
+use core::fmt;
+use core::mem;
+use core::ops::{Bound, RangeBounds, RangeInclusive};
+use core::slice;
+use alloc::vec::{self, Vec};
+#[cfg(feature = "allocator_api")]
+use core::alloc::Allocator;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+pub mod array_list;
+#[cfg(feature = "std")]
+pub mod disk;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod raw;
+#[cfg(feature = "serde")]
+mod serde_impl;
+pub mod storage;
+
+pub use array_list::ArrayList;
+#[cfg(feature = "std")]
+pub use disk::DiskList;
+pub use raw::{ArchivedList, RawList};
+#[cfg(feature = "serde")]
+pub use serde_impl::ListLayout;
+pub use storage::{Storage, TryReserveError};
+use storage::{Alloc, AllocStorage};
 
 type Ix = usize;
 /// **END** is the "null" pointer of the link indexes
-const END: usize = std::usize::MAX;
+const END: usize = usize::MAX;
 
 #[derive(Clone, Debug)]
 pub struct Node<T> {
     /// Prev, Next.
     link: [usize; 2],
+    /// Index of the node's entry in the slot table.
+    slot: usize,
     pub value: T,
 }
 
@@ -20,8 +74,9 @@ impl<T> Node<T> {
     fn new(value: T, prev: Ix, next: Ix) -> Self
     {
         Node {
-            value: value,
+            value,
             link: [prev, next],
+            slot: END,
         }
     }
     fn prev(&self) -> Ix { self.link[0] }
@@ -36,7 +91,11 @@ impl<T> Node<T> {
 ///
 /// * O(1) insert and remove both at front and back.
 /// * O(1) insert anywhere if you have a cursor to that position.
-/// * Only use of **unsafe** is an unavoidable use for **IterMut**.
+/// * Stable handles to elements, that can be turned back into cursors in O(1).
+/// * **List** itself only uses **unsafe** for **IterMut**. The rest of the crate
+///   uses it for the uninitialized nodes of **ArrayList**, **InlineStorage**
+///   and **AllocStorage**, for the byte views of **RawList**, **ArchivedList**
+///   and **DiskList**, and at the C boundary in the **ffi** module.
 ///
 ///
 /// ## Implementation
@@ -44,13 +103,18 @@ impl<T> Node<T> {
 /// It is similar to a linked list in a language like C, except instead of pointers we
 /// use indices into a backing vector.
 ///
-/// The list is just a vector, and indices to the head and tail:
+/// The list is just a vector, and indices to the head and tail. The vector
+/// is a `Vec` by default, but can be any **Storage**, and so can the slot
+/// table described below:
 ///
 /// ```ignore
-/// struct List<T> {
+/// struct List<T, S = Vec<Node<T>>, L = Vec<Slot>> {
 ///     /// Head, Tail
 ///     link: [usize; 2],
-///     nodes: Vec<Node<T>>,
+///     nodes: S,
+///     slots: L,
+///     /// First free slot
+///     free: usize,
 /// }
 /// ```
 ///
@@ -60,6 +124,7 @@ impl<T> Node<T> {
 /// struct Node<T> {
 ///     /// Prev, Next.
 ///     link: [usize; 2],
+///     slot: usize,
 ///     value: T,
 /// }
 /// ```
@@ -72,6 +137,13 @@ impl<T> Node<T> {
 /// We don't always have to check for this case, we can just access the nodes
 /// vector using *.get()* or *.get_mut()*; a “null” link is the **None** case.
 ///
+/// Removing a node moves the last node of the vector into its place, so a
+/// vector index is not a stable name for an element. Each node instead owns an
+/// entry in the slot table, which records where the node currently is.
+/// A **Handle** is a slot index plus the slot's generation, which is bumped
+/// when the slot's element is removed, so that old handles become invalid
+/// before the slot is reused.
+///
 /// ## To do
 ///
 /// List could be generic over the index type, so that internal
@@ -80,11 +152,111 @@ impl<T> Node<T> {
 /// With some cleanup we can use unchecked indexing — but it's not guaranteed
 /// to make any difference.
 ///
-#[derive(Clone, Debug)]
-pub struct List<T> {
+#[derive(Debug)]
+pub struct List<T, S = Vec<Node<T>>, L = Vec<Slot>> {
     /// Head, Tail
     link: [usize; 2],
-    nodes: Vec<Node<T>>,
+    nodes: S,
+    slots: L,
+    /// First free slot
+    free: usize,
+    marker: PhantomData<T>,
+}
+
+/// An entry in the slot table of a **List**, which maps the handles of the
+/// elements to their nodes.
+#[derive(Copy, Clone, Debug)]
+pub struct Slot {
+    /// Index of the node, or the next free slot if this slot is free.
+    node: usize,
+    gen: usize,
+}
+
+/// A **Handle** is a stable reference to an element of a **List**.
+///
+/// It stays valid while the element is in the list, no matter which other
+/// elements are inserted or removed, and can be used to create a cursor at
+/// the element in O(1) time.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Handle {
+    slot: usize,
+    gen: usize,
+}
+
+/// The error returned when there is no room to insert an element; it
+/// hands the element back.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TryPushError<T>
+{
+    value: T,
+    error: TryReserveError,
+}
+
+impl<T> TryPushError<T>
+{
+    /// Return the element that could not be inserted.
+    pub fn into_value(self) -> T { self.value }
+
+    /// Return the reason why there was no room.
+    pub fn error(&self) -> &TryReserveError { &self.error }
+}
+
+impl<T> fmt::Display for TryPushError<T>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug> Error for TryPushError<T>
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        Some(&self.error)
+    }
+}
+
+/// The error returned by *List::try_extend* when there is no room for all
+/// the elements; it hands back the elements that were not inserted.
+#[derive(Clone, Debug)]
+pub struct TryExtendError<T, I>
+{
+    /// Elements already taken from the iterator, in order
+    values: Vec<T>,
+    rest: I,
+    error: TryReserveError,
+}
+
+impl<T, I: Iterator<Item=T>> TryExtendError<T, I>
+{
+    /// Return an iterator of the elements that could not be inserted: those
+    /// already taken from the iterator, followed by the rest of it.
+    pub fn into_values(self) -> Chain<vec::IntoIter<T>, I>
+    {
+        self.values.into_iter().chain(self.rest)
+    }
+
+    /// Return the reason why there was no room.
+    pub fn error(&self) -> &TryReserveError { &self.error }
+}
+
+impl<T, I> fmt::Display for TryExtendError<T, I>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: fmt::Debug, I: fmt::Debug> Error for TryExtendError<T, I>
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        Some(&self.error)
+    }
 }
 
 /// Represent one of the two ends of the list
@@ -109,12 +281,14 @@ impl Terminal
     pub fn index(&self) -> usize { *self as usize }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Debug)]
 pub struct Iter<'a, T: 'a>
 {
     link: [usize; 2],
     nodes: &'a [Node<T>],
-    taken: usize,
+    slots: &'a [Slot],
+    /// Number of elements left
+    len: usize,
 }
 
 #[derive(Debug)]
@@ -122,16 +296,73 @@ pub struct IterMut<'a, T: 'a>
 {
     link: [usize; 2],
     nodes: &'a mut [Node<T>],
-    taken: usize,
+    slots: &'a [Slot],
+    /// Number of elements left
+    len: usize,
 }
 
 /// A cursor points to a location in a list, and you can step the
 /// cursor forward and backward.
+///
+/// The cursor is either positioned at an element, the *current* element,
+/// or at the end of the list. The end is a “ghost” non-element that sits
+/// after the last and before the first element, just like in the cursors
+/// of *std::collections::LinkedList*. An empty list has only the end position.
+///
+/// By default the cursor wraps around: stepping forward from the end moves
+/// it to the first element, and stepping backward from the first element
+/// moves it to the end. With *.set_wrapping(false)* it instead stays put
+/// in those two cases.
+#[derive(Debug)]
+pub struct Cursor<'a, T: 'a, S: 'a = Vec<Node<T>>, L: 'a = Vec<Slot>>
+{
+    pos: usize,
+    /// Ordinal position of `pos` in the list, if known; the list's length
+    /// at the end.
+    index: Option<usize>,
+    wrap: bool,
+    list: &'a mut List<T, S, L>,
+}
+
+/// A read-only view of a contiguous part of a **List**, created by *List::range*.
+#[derive(Debug)]
+pub struct SubList<'a, T: 'a>
+{
+    /// First, Last
+    link: [usize; 2],
+    len: usize,
+    nodes: &'a [Node<T>],
+    slots: &'a [Slot],
+}
+
+/// A read-only cursor into a **SubList**.
+///
+/// It works like **Cursor**, except that the first and last elements and
+/// the end position are those of the sublist, so it never leaves the range.
 #[derive(Debug)]
-pub struct Cursor<'a, T: 'a>
+pub struct SubCursor<'a, T: 'a>
 {
     pos: usize,
-    list: &'a mut List<T>,
+    index: usize,
+    list: SubList<'a, T>,
+}
+
+/// An iterator of the elements removed by *List::drain* and related methods.
+///
+/// The elements are already unlinked from the list when the **Drain** is
+/// created, and are taken from the end of the storage as the **Drain** is
+/// iterated; dropping it drops any remaining elements.
+///
+/// If the **Drain** is leaked, the remaining elements are leaked too, and
+/// the List is left in an unspecified state.
+#[derive(Debug)]
+pub struct Drain<'a, T: 'a, S: Storage<Node<T>> + 'a = Vec<Node<T>>>
+{
+    nodes: &'a mut S,
+    link: [usize; 2],
+    /// Number of elements left
+    len: usize,
+    marker: PhantomData<T>,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -144,6 +375,10 @@ pub enum Seek {
     Head,
     /// Seek to the end.
     Tail,
+    /// Seek to the element at index *n*, or at most to the end.
+    To(usize),
+    /// Seek to *n* steps before the end, or at most to the beginning.
+    FromTail(usize),
 }
 
 impl<T> List<T>
@@ -156,6 +391,59 @@ impl<T> List<T>
     {
         List{
             link: [END; 2], nodes: Vec::with_capacity(cap),
+            slots: Vec::with_capacity(cap), free: END,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, A: Alloc + Clone> List<T, AllocStorage<Node<T>, A>, AllocStorage<Slot, A>>
+{
+    /// Create a new **List** that allocates its nodes and slot table with
+    /// **alloc**.
+    pub fn with_alloc(alloc: A) -> Self
+    {
+        List::with_storages(AllocStorage::new_in(alloc.clone()), AllocStorage::new_in(alloc))
+    }
+}
+
+#[cfg(feature = "allocator_api")]
+impl<T, A: Allocator + Clone> List<T, Vec<Node<T>, A>, Vec<Slot, A>>
+{
+    /// Create a new **List** that allocates its nodes and slot table with
+    /// **alloc**.
+    pub fn new_in(alloc: A) -> Self
+    {
+        List::with_storages(Vec::new_in(alloc.clone()), Vec::new_in(alloc))
+    }
+}
+
+impl<T, S: Storage<Node<T>>> List<T, S>
+{
+    /// Create a new **List** that keeps its elements in **storage**, and
+    /// its slot table in a `Vec`.
+    ///
+    /// **Panics** if **storage** is not empty.
+    pub fn with_storage(storage: S) -> Self
+    {
+        List::with_storages(storage, Vec::new())
+    }
+}
+
+impl<T, S: Storage<Node<T>>, L: Storage<Slot>> List<T, S, L>
+{
+    /// Create a new **List** that keeps its elements in **nodes**, and its
+    /// slot table in **slots**.
+    ///
+    /// **Panics** if either storage is not empty.
+    pub fn with_storages(nodes: S, slots: L) -> Self
+    {
+        assert!(nodes.is_empty(), "List::with_storages: the node storage is not empty");
+        assert!(slots.is_empty(), "List::with_storages: the slot storage is not empty");
+        List {
+            link: [END; 2], nodes,
+            slots, free: END,
+            marker: PhantomData,
         }
     }
 
@@ -168,36 +456,360 @@ impl<T> List<T>
         self.nodes.len()
     }
 
+    /// Return **true** if the List is empty.
+    pub fn is_empty(&self) -> bool
+    {
+        self.nodes.is_empty()
+    }
+
+    /// Return the number of elements the List can hold without
+    /// reallocating.
+    pub fn capacity(&self) -> usize
+    {
+        self.nodes.capacity()
+    }
+
     /// Return an iterator.
-    pub fn iter(&self) -> Iter<T>
+    pub fn iter(&self) -> Iter<'_, T>
     {
         Iter {
             link: self.link,
-            nodes: &*self.nodes,
-            taken: 0,
+            nodes: &self.nodes,
+            slots: &self.slots,
+            len: self.len(),
         }
     }
 
     /// Return an iterator.
-    pub fn iter_mut(&mut self) -> IterMut<T>
+    pub fn iter_mut(&mut self) -> IterMut<'_, T>
     {
         IterMut {
             link: self.link,
-            nodes: &mut *self.nodes,
-            taken: 0,
+            len: self.nodes.len(),
+            nodes: &mut self.nodes,
+            slots: &self.slots,
         }
     }
 
-    /// Return a new cursor, focused before the head of the List.
-    pub fn cursor(&mut self) -> Cursor<T>
+    /// Return an iterator that starts at the element of **handle** and
+    /// continues to the end of the List, then wraps around to the beginning,
+    /// stopping after each element has been visited once.
+    ///
+    /// Return **None** if the handle is not valid.
+    pub fn cycle_from(&self, handle: Handle) -> Option<Cycle<'_, T>>
+    {
+        let index = self.handle_index(handle)?;
+        let prev = match self.nodes[index].prev() {
+            END => self.tail(),
+            prev => prev,
+        };
+        Some(Cycle {
+            iter: Iter {
+                link: [index, prev],
+                nodes: &self.nodes,
+                slots: &self.slots,
+                len: self.len(),
+            },
+            ends: self.link,
+        })
+    }
+
+    /// Return an iterator that starts at the element of **handle**, and
+    /// cycles through the List endlessly.
+    ///
+    /// Return **None** if the handle is not valid.
+    pub fn cycle_forever_from(&self, handle: Handle) -> Option<CycleForever<'_, T>>
+    {
+        self.cycle_from(handle).map(|cycle| CycleForever { cycle })
+    }
+
+    /// Return an iterator of all runs of **N** consecutive elements, in order.
+    ///
+    /// **Panics** if **N** is zero.
+    pub fn windows<const N: usize>(&self) -> Windows<'_, T, N>
+    {
+        assert!(N != 0, "List::windows: window size must be non-zero");
+        Windows { iter: self.iter() }
+    }
+
+    /// Return an iterator of the elements in storage order, which is
+    /// **not** the order of the List.
+    ///
+    /// This is a linear scan of the backing vector, so it is faster than
+    /// *.iter()* when the order doesn't matter.
+    pub fn values(&self) -> Values<'_, T>
+    {
+        Values { iter: self.nodes.iter() }
+    }
+
+    /// Return an iterator of mutable references to the elements in
+    /// storage order, which is **not** the order of the List.
+    ///
+    /// This is a linear scan of the backing vector, so it is faster than
+    /// *.iter_mut()* when the order doesn't matter.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, T>
+    {
+        ValuesMut { iter: self.nodes.iter_mut() }
+    }
+
+    /// Call **f** on each element, in storage order rather than the order
+    /// of the List.
+    pub fn for_each_unordered<F>(&self, f: F)
+        where F: FnMut(&T)
+    {
+        self.values().for_each(f)
+    }
+
+    /// Call **f** on each element, in storage order rather than the order
+    /// of the List.
+    pub fn for_each_unordered_mut<F>(&mut self, f: F)
+        where F: FnMut(&mut T)
+    {
+        self.values_mut().for_each(f)
+    }
+
+    /// Return an iterator of the handles of the elements, in order.
+    pub fn handles(&self) -> Handles<'_, T>
+    {
+        Handles { iter: self.iter() }
+    }
+
+    /// Return an iterator that starts at the element of **handle** and
+    /// continues to the end of the List, or **None** if the handle is not valid.
+    pub fn iter_from(&self, handle: Handle) -> Option<Iter<'_, T>>
+    {
+        let index = self.handle_index(handle)?;
+        Some(Iter {
+            link: [index, self.tail()],
+            nodes: &self.nodes,
+            slots: &self.slots,
+            len: self.len() - self.ordinal(index),
+        })
+    }
+
+    /// Return an iterator that starts at the element of **handle** and
+    /// continues backward to the beginning of the List, or **None** if the
+    /// handle is not valid.
+    pub fn iter_rev_from(&self, handle: Handle) -> Option<Rev<Iter<'_, T>>>
+    {
+        let index = self.handle_index(handle)?;
+        Some(Iter {
+            link: [self.head(), index],
+            nodes: &self.nodes,
+            slots: &self.slots,
+            len: self.ordinal(index) + 1,
+        }.rev())
+    }
+
+    /// Return a new cursor, positioned at the head of the List.
+    pub fn cursor(&mut self) -> Cursor<'_, T, S, L>
     {
         Cursor {
             pos: self.head(),
+            index: Some(0),
+            wrap: true,
+            list: self,
+        }
+    }
+
+    /// Return a new cursor, positioned at the element of **handle**,
+    /// or **None** if the handle is not valid.
+    ///
+    /// This is O(1); the cursor's *.index()* is computed when first needed.
+    pub fn cursor_at(&mut self, handle: Handle) -> Option<Cursor<'_, T, S, L>>
+    {
+        let pos = self.handle_index(handle)?;
+        Some(Cursor {
+            pos,
+            index: None,
+            wrap: true,
+            list: self,
+        })
+    }
+
+    /// Return a new cursor, positioned at the node at **index** of the
+    /// nodes vector, or at the end if it is END.
+    #[cfg(feature = "std")]
+    fn cursor_at_index(&mut self, index: usize) -> Cursor<'_, T, S, L>
+    {
+        Cursor {
+            pos: index,
+            index: None,
+            wrap: true,
             list: self,
         }
     }
 
+    /// Return a view of the elements from the first to the last handle of
+    /// **range**, inclusive.
+    ///
+    /// Return **None** if either handle is not valid, or if the last handle
+    /// does not come at or after the first in the list.
+    pub fn range(&self, range: RangeInclusive<Handle>) -> Option<SubList<'_, T>>
+    {
+        let (first, last) = range.into_inner();
+        let first = self.handle_index(first)?;
+        let last = self.handle_index(last)?;
+        let mut len = 1;
+        let mut index = first;
+        while index != last {
+            index = self.nodes[index].next();
+            if index == END {
+                return None;
+            }
+            len += 1;
+        }
+        Some(SubList {
+            link: [first, last],
+            len,
+            nodes: &self.nodes,
+            slots: &self.slots,
+        })
+    }
+
+    /// Return the index of the node of **handle**, if it is valid.
+    fn handle_index(&self, handle: Handle) -> Option<usize>
+    {
+        match self.slots.get(handle.slot) {
+            Some(s) if s.gen == handle.gen => Some(s.node),
+            _ => None,
+        }
+    }
+
+    /// Return the handle of the node at **idx**.
+    fn handle(&self, idx: usize) -> Handle
+    {
+        make_handle(&self.slots, self.nodes[idx].slot)
+    }
+
+    /// Return the position of the node at **idx** in traversal order, or
+    /// the length of the list if **idx** is END.
+    ///
+    /// This walks from the node towards both ends at once, until either
+    /// is reached.
+    fn ordinal(&self, idx: usize) -> usize
+    {
+        if idx == END {
+            return self.len();
+        }
+        let mut back = idx;
+        let mut front = idx;
+        let mut steps = 0;
+        loop {
+            back = self.nodes[back].prev();
+            if back == END {
+                return steps;
+            }
+            front = self.nodes[front].next();
+            if front == END {
+                return self.len() - 1 - steps;
+            }
+            steps += 1;
+        }
+    }
+
+    /// Make sure that there is room to push a node.
+    ///
+    /// **Panics** if the storage is full and can not grow.
+    fn reserve_node(&mut self)
+    {
+        if self.nodes.len() == self.nodes.capacity() {
+            self.nodes.reserve(1);
+            assert!(self.nodes.len() < self.nodes.capacity(), "List: the storage is full");
+        }
+        if self.free == END && self.slots.len() == self.slots.capacity() {
+            self.slots.reserve(1);
+            assert!(self.slots.len() < self.slots.capacity(), "List: the slot storage is full");
+        }
+    }
+
+    /// Make sure that there is room to push a node, or return an error.
+    fn try_reserve_node(&mut self) -> Result<(), TryReserveError>
+    {
+        if self.nodes.len() == self.nodes.capacity() {
+            self.nodes.try_reserve(1)?;
+        }
+        if self.free == END && self.slots.len() == self.slots.capacity() {
+            self.slots.try_reserve(1)?;
+        }
+        Ok(())
+    }
+
+    /// Reserve room for at least **additional** more elements, or return an
+    /// error if that is not possible.
+    ///
+    /// The elements and handles of the List are unchanged on error, but the
+    /// slot storage may have grown.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>
+    {
+        // Each element has a slot; the rest of the slots are free.
+        let free_slots = self.slots.len() - self.nodes.len();
+        self.slots.try_reserve(additional.saturating_sub(free_slots))?;
+        self.nodes.try_reserve(additional)?;
+        Ok(())
+    }
+
+    /// Push **node** to the nodes vector and give it a slot.
+    fn push_node(&mut self, mut node: Node<T>)
+    {
+        let index = self.nodes.len();
+        node.slot = match self.free {
+            END => self.slots.len(),
+            free => free,
+        };
+        if self.nodes.push(node).is_err() {
+            panic!("List: the storage is full");
+        }
+        match self.free {
+            END => if self.slots.push(Slot { node: index, gen: 0 }).is_err() {
+                panic!("List: the slot storage is full");
+            },
+            free => {
+                self.free = self.slots[free].node;
+                self.slots[free].node = index;
+            }
+        }
+    }
+
+    /// Return a cursor positioned at the first element that satisfies **f**,
+    /// so that *.next()* returns it, or **None** if there is no such element.
+    pub fn find<F>(&mut self, f: F) -> Option<Cursor<'_, T, S, L>>
+        where F: FnMut(&T) -> bool
+    {
+        let mut c = self.cursor();
+        c.find_next(f)?;
+        c.prev();
+        Some(c)
+    }
+
+    /// Return a cursor positioned at the last element that satisfies **f**,
+    /// so that *.next()* returns it, or **None** if there is no such element.
+    pub fn rfind<F>(&mut self, f: F) -> Option<Cursor<'_, T, S, L>>
+        where F: FnMut(&T) -> bool
+    {
+        let mut c = self.cursor();
+        c.seek(Seek::Tail);
+        c.find_prev(f)?;
+        Some(c)
+    }
+
     fn push_terminal(&mut self, value: T, term: Terminal)
+    {
+        self.reserve_node();
+        self.link_terminal(value, term);
+    }
+
+    fn try_push_terminal(&mut self, value: T, term: Terminal) -> Result<(), TryPushError<T>>
+    {
+        if let Err(error) = self.try_reserve_node() {
+            return Err(TryPushError { value, error });
+        }
+        self.link_terminal(value, term);
+        Ok(())
+    }
+
+    /// Push a node with **value** at the end **term**; there must be room.
+    fn link_terminal(&mut self, value: T, term: Terminal)
     {
         let t = term as usize;
         let index = self.nodes.len();
@@ -209,7 +821,107 @@ impl<T> List<T>
             Some(n) => n.link[t] = index,
         }
         self.link[t] = index;
-        self.nodes.push(node);
+        self.push_node(node);
+    }
+
+    /// Link the elements of **iter**, in order, before the node at **next**,
+    /// or at the end if **next** is END.
+    ///
+    /// Return the index of the first inserted node, or **next** if **iter**
+    /// was empty.
+    fn link_iter_before<I>(&mut self, next: usize, iter: I) -> usize
+        where I: IntoIterator<Item=T>
+    {
+        let iter = iter.into_iter();
+        let (low, _) = iter.size_hint();
+        // Only a hint; each node is reserved for when it is pushed.
+        let _ = self.try_reserve(low);
+        match self.try_link_iter_before(next, iter, Self::try_reserve_node) {
+            Ok(first) => first,
+            Err((_, error)) => panic!("List: {}", error),
+        }
+    }
+
+    /// Like *link_iter_before*, but call **reserve** before pushing each
+    /// node. If it fails, remove the nodes pushed so far, so that the List
+    /// is unchanged, and return the error with the elements taken from
+    /// **iter**, in order.
+    fn try_link_iter_before<I, R>(&mut self, next: usize, iter: I, mut reserve: R)
+        -> Result<usize, (Vec<T>, TryReserveError)>
+        where I: IntoIterator<Item=T>,
+              R: FnMut(&mut Self) -> Result<(), TryReserveError>,
+    {
+        let prev = match self.nodes.get(next) {
+            None => self.tail(),
+            Some(n) => n.prev(),
+        };
+        let first = self.nodes.len();
+        let slots_len = self.slots.len();
+        let mut last = prev;
+        for elt in iter {
+            if let Err(error) = reserve(self) {
+                let mut values = self.unpush_nodes(first, slots_len);
+                values.push(elt);
+                return Err((values, error));
+            }
+            let index = self.nodes.len();
+            self.push_node(Node::new(elt, last, index + 1));
+            last = index;
+        }
+
+        if last == prev {
+            return Ok(next);
+        }
+        self.nodes[last].set_next(next);
+        match self.nodes.get_mut(prev) {
+            None => self.link[0] = first, // inserted at the head
+            Some(n) => n.set_next(first),
+        }
+        match self.nodes.get_mut(next) {
+            None => self.link[1] = last, // inserted at the tail
+            Some(n) => n.set_prev(last),
+        }
+        Ok(first)
+    }
+
+    /// Remove the unlinked nodes from index **first** onwards, that were
+    /// pushed when there were **slots_len** slots, and give back their
+    /// slots, in reverse order, so that the free list is restored.
+    ///
+    /// Return the values of the removed nodes, in the order they were pushed.
+    fn unpush_nodes(&mut self, first: usize, slots_len: usize) -> Vec<T>
+    {
+        let mut values = Vec::new();
+        while self.nodes.len() > first {
+            let node = self.nodes.pop().unwrap();
+            if node.slot < slots_len {
+                self.slots[node.slot].node = self.free;
+                self.free = node.slot;
+            }
+            values.push(node.value);
+        }
+        self.slots.truncate(slots_len);
+        values.reverse();
+        values
+    }
+
+    /// Append the elements of **iter** to the List, or return an error
+    /// if there is not room for all of them.
+    ///
+    /// The List is unchanged on error, and the error hands back the elements
+    /// taken from **iter** so far, and the rest of it.
+    pub fn try_extend<I>(&mut self, iter: I) -> Result<(), TryExtendError<T, I::IntoIter>>
+        where I: IntoIterator<Item=T>
+    {
+        let mut iter = iter.into_iter();
+        let (low, _) = iter.size_hint();
+        if let Err(error) = self.try_reserve(low) {
+            return Err(TryExtendError { values: Vec::new(), rest: iter, error });
+        }
+        match self.try_link_iter_before(END, iter.by_ref(), Self::try_reserve_node) {
+            Ok(_) => Ok(()),
+            Err((values, error)) => Err(TryExtendError { values, rest: iter, error }),
+        }
     }
 
     /// Insert an element at the beginning of the List.
@@ -222,6 +934,18 @@ impl<T> List<T>
         self.push_terminal(value, Terminal::Tail)
     }
 
+    /// Insert an element at the beginning of the List, or return it in
+    /// the error if there is no room. The List is unchanged on error.
+    pub fn try_push_front(&mut self, value: T) -> Result<(), TryPushError<T>> {
+        self.try_push_terminal(value, Terminal::Head)
+    }
+
+    /// Insert an element at the end of the List, or return it in the
+    /// error if there is no room. The List is unchanged on error.
+    pub fn try_push_back(&mut self, value: T) -> Result<(), TryPushError<T>> {
+        self.try_push_terminal(value, Terminal::Tail)
+    }
+
     /// "unlink" the node at idx
     fn prepare_remove(&mut self, idx: usize)
     {
@@ -263,6 +987,8 @@ impl<T> List<T>
         }
 
         self.prepare_move(moved_index, free_spot);
+        let slot = self.nodes[moved_index].slot;
+        self.slots[slot].node = free_spot;
         if self.head() == moved_index {
             self.link[0] = free_spot;
         }
@@ -271,46 +997,326 @@ impl<T> List<T>
         }
     }
 
-    /// Remove the element at either head or tail
-    fn pop_terminal(&mut self, term: Terminal) -> Option<T>
+    /// Unlink and remove the node at **idx**, moving the last node of the
+    /// vector into the free spot.
+    fn remove_index(&mut self, idx: usize) -> T
     {
-        let t = term as usize;
-        if self.link[t] == END {
-            return None
+        let prev = self.nodes[idx].prev();
+        let next = self.nodes[idx].next();
+        self.prepare_remove(idx);
+        if self.head() == idx {
+            self.link[0] = next;
         }
-        let h = self.link[t];
-        let new_terminal = self.nodes[h].link[1 - t];
-        self.prepare_remove(h);
-
-        self.link[t] = new_terminal;
-        if self.link[t] == END {
-            self.link[1 - t] = END;
-        } else {
-            let moved_index = self.nodes.len() - 1; // last index moves.
-            self.prepare_swap(h, moved_index);
+        if self.tail() == idx {
+            self.link[1] = prev;
         }
-        let removed_node = self.nodes.swap_remove(h);
-        Some(removed_node.value)
+        let moved_index = self.nodes.len() - 1; // last index moves.
+        self.prepare_swap(idx, moved_index);
+        let removed_node = self.nodes.swap_remove(idx);
+        self.free_slot(removed_node.slot);
+        removed_node.value
     }
 
-    /// Remove the element at the beginning of the List and return it,
-    /// or return **None** if the List is empty.
-    pub fn pop_front(&mut self) -> Option<T>
+    /// Invalidate handles to the slot and put it on the free list.
+    fn free_slot(&mut self, index: usize)
     {
-        self.pop_terminal(Terminal::Head)
+        let slot = &mut self.slots[index];
+        slot.gen = slot.gen.wrapping_add(1);
+        slot.node = self.free;
+        self.free = index;
     }
 
-    /// Remove the element at the end of the List and return it,
-    /// or return **None** if the List is empty.
+    /// Unlink the run of up to **count** nodes that starts at **first**, and
+    /// move the removed nodes to the end of the vector, so that they can be
+    /// split off all at once.
+    ///
+    /// Nodes are moved only to fill the holes the run leaves below the new
+    /// length, so that at most **count** nodes move. The removed nodes
+    /// are left in no particular order; the next link of each is
+    /// relabeled with its position in the run.
+    ///
+    /// Return the number of nodes removed, and the index of the node after
+    /// the run (or END).
+    fn cut_run(&mut self, first: usize, count: usize) -> (usize, usize)
+    {
+        if count == 0 || first == END {
+            return (0, first);
+        }
+        let prev = self.nodes[first].prev();
+
+        // Walk the run: free the slots, and mark each node as removed with an
+        // END slot. The prev links are reused to chain the run.
+        let mut index = first;
+        let mut last = first;
+        let mut removed = 0;
+        while index != END && removed < count {
+            let n = &mut self.nodes[index];
+            let next = n.next();
+            let slot = mem::replace(&mut n.slot, END);
+            n.link = [next, removed];
+            self.free_slot(slot);
+            removed += 1;
+            last = index;
+            index = next;
+        }
+        self.nodes[last].set_prev(END);
+        let mut after = index;
+
+        // unlink the run
+        match self.nodes.get_mut(prev) {
+            None => self.link[0] = after,
+            Some(n) => n.set_next(after),
+        }
+        match self.nodes.get_mut(after) {
+            None => self.link[1] = prev,
+            Some(n) => n.set_prev(prev),
+        }
+
+        // Fill each hole below the new length with a node from above it
+        let new_len = self.nodes.len() - removed;
+        let mut hole = first;
+        let mut mover = new_len;
+        while hole != END {
+            let next_hole = self.nodes[hole].prev();
+            if hole < new_len {
+                while self.nodes[mover].slot == END {
+                    mover += 1;
+                }
+                self.nodes.swap(hole, mover);
+                self.prepare_move(hole, hole);
+                let slot = self.nodes[hole].slot;
+                self.slots[slot].node = hole;
+                for link in self.link.iter_mut().chain(Some(&mut after)) {
+                    if *link == mover {
+                        *link = hole;
+                    }
+                }
+                mover += 1;
+            }
+            hole = next_hole;
+        }
+        (removed, after)
+    }
+
+    /// Remove the elements in the **range** of indices from the List, and
+    /// return them in an iterator.
+    ///
+    /// **Panics** if the range is out of bounds.
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, S>
+        where R: RangeBounds<usize>
+    {
+        let len = self.len();
+        let start = match range.start_bound() {
+            Bound::Included(&i) => i,
+            Bound::Excluded(&i) => i + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&i) => i + 1,
+            Bound::Excluded(&i) => i,
+            Bound::Unbounded => len,
+        };
+        assert!(start <= end && end <= len,
+                "List::drain: range {}..{} out of bounds for length {}", start, end, len);
+        let first = {
+            let mut c = self.cursor();
+            c.seek(Seek::To(start));
+            c.pos
+        };
+        self.drain_run(first, end - start).0
+    }
+
+    /// Remove the elements from **first** to **last**, inclusive, and
+    /// return them in an iterator.
+    ///
+    /// Return **None** if either handle is not valid, or if **last** does not
+    /// come at or after **first** in the list.
+    pub fn drain_range(&mut self, first: Handle, last: Handle) -> Option<Drain<'_, T, S>>
+    {
+        let first = self.handle_index(first)?;
+        let last = self.handle_index(last)?;
+        let mut count = 1;
+        let mut index = first;
+        while index != last {
+            index = self.nodes[index].next();
+            if index == END {
+                return None;
+            }
+            count += 1;
+        }
+        Some(self.drain_run(first, count).0)
+    }
+
+    /// Remove the run of up to **count** nodes that starts at **first**, and
+    /// return the elements in an iterator, and the index of the node after
+    /// the run.
+    fn drain_run(&mut self, first: usize, count: usize) -> (Drain<'_, T, S>, usize)
+    {
+        let (removed, after) = self.cut_run(first, count);
+        let base = self.nodes.len() - removed;
+
+        // Put the removed nodes in order, using their positions in the run,
+        // and link them to each other.
+        for i in 0..removed {
+            loop {
+                let pos = self.nodes[base + i].next();
+                if pos == i {
+                    break;
+                }
+                self.nodes.swap(base + i, base + pos);
+            }
+        }
+        for i in 0..removed {
+            let prev = if i == 0 { END } else { base + i - 1 };
+            let next = if i + 1 == removed { END } else { base + i + 1 };
+            self.nodes[base + i].link = [prev, next];
+        }
+        let link = if removed == 0 { [END, END] } else { [base, base + removed - 1] };
+        let drain = Drain {
+            nodes: &mut self.nodes,
+            link,
+            len: removed,
+            marker: PhantomData,
+        };
+        (drain, after)
+    }
+
+    /// Remove the element at either head or tail
+    fn pop_terminal(&mut self, term: Terminal) -> Option<T>
+    {
+        match self.link[term.index()] {
+            END => None,
+            h => Some(self.remove_index(h)),
+        }
+    }
+
+    /// Remove the element at the beginning of the List and return it,
+    /// or return **None** if the List is empty.
+    pub fn pop_front(&mut self) -> Option<T>
+    {
+        self.pop_terminal(Terminal::Head)
+    }
+
+    /// Remove the element at the end of the List and return it,
+    /// or return **None** if the List is empty.
     pub fn pop_back(&mut self) -> Option<T>
     {
         self.pop_terminal(Terminal::Tail)
     }
 
+    /// Remove consecutive elements that **same_bucket** considers equal,
+    /// keeping the first of each run.
+    ///
+    /// **same_bucket** is passed the later and the earlier element of each
+    /// adjacent pair, in that order, just like *Vec::dedup_by*.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+        where F: FnMut(&mut T, &mut T) -> bool
+    {
+        let mut index = self.head();
+        while index != END {
+            let next = self.nodes[index].next();
+            if next == END {
+                break;
+            }
+            let duplicate = {
+                let (a, b) = index_twice(&mut self.nodes, next, index);
+                same_bucket(&mut a.value, &mut b.value)
+            };
+            if duplicate {
+                let moved_index = self.nodes.len() - 1;
+                self.remove_index(next);
+                if index == moved_index {
+                    index = next;
+                }
+            } else {
+                index = next;
+            }
+        }
+    }
+
+    /// Remove consecutive elements that map to the same key.
+    pub fn dedup_by_key<F, K>(&mut self, mut key: F)
+        where F: FnMut(&mut T) -> K,
+              K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Remove consecutive repeated elements.
+    pub fn dedup(&mut self)
+        where T: PartialEq
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    /// Split the List into the elements that satisfy **f** and those that don't,
+    /// both in their original order.
+    pub fn partition<F>(mut self, mut f: F) -> (Self, Self)
+        where F: FnMut(&T) -> bool,
+              S: Default,
+              L: Default,
+    {
+        let mut left = List::default();
+        let mut right = List::default();
+        while let Some(elt) = self.pop_front() {
+            if f(&elt) {
+                left.push_back(elt);
+            } else {
+                right.push_back(elt);
+            }
+        }
+        (left, right)
+    }
+
+    /// Relink the List so that the elements that satisfy **f** come first,
+    /// followed by those that don't. The relative order within each group
+    /// is kept, and no elements are moved in memory.
+    ///
+    /// Return the number of elements that satisfied **f**.
+    pub fn stable_partition<F>(&mut self, mut f: F) -> usize
+        where F: FnMut(&T) -> bool
+    {
+        // Head and tail of the two sublists we build
+        let mut front = [END; 2];
+        let mut back = [END; 2];
+        let mut count = 0;
+        let mut index = self.head();
+        while let Some(n) = self.nodes.get(index) {
+            let next = n.next();
+            let part = if f(&n.value) {
+                count += 1;
+                &mut front
+            } else {
+                &mut back
+            };
+            self.nodes[index].set_prev(part[1]);
+            match self.nodes.get_mut(part[1]) {
+                None => part[0] = index,
+                Some(tailn) => tailn.set_next(index),
+            }
+            part[1] = index;
+            index = next;
+        }
+
+        if front[0] == END {
+            self.link = back;
+        } else if back[0] == END {
+            self.link = front;
+        } else {
+            self.nodes[front[1]].set_next(back[0]);
+            self.nodes[back[0]].set_prev(front[1]);
+            self.link = [front[0], back[1]];
+        }
+        if let Some(n) = self.nodes.get_mut(self.link[1]) {
+            n.set_next(END);
+        }
+        count
+    }
+
     /// Reorder internal datastructure into traversal order.
     pub fn linearize(&mut self)
     {
-        if self.len() == 0 {
+        if self.is_empty() {
             return;
         }
 
@@ -335,59 +1341,221 @@ impl<T> List<T>
         self.link[1] = self.len() - 1;
         self.nodes[self.link[0]].set_prev(END);
         self.nodes[self.link[1]].set_next(END);
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            self.slots[node.slot].node = index;
+        }
+    }
+
+    /// Return a clone of the List whose elements are stored in traversal
+    /// order, like after *linearize*.
+    ///
+    /// Handles to elements of this List are valid for the corresponding
+    /// elements of the clone.
+    pub fn clone_linearized(&self) -> Self
+        where T: Clone,
+              S: Default,
+              L: Clone,
+    {
+        let len = self.len();
+        let mut nodes = S::default();
+        nodes.reserve(len);
+        let mut slots = self.slots.clone();
+        let mut iter = self.iter();
+        while let Some(n) = iter.next_node(Terminal::Head) {
+            let index = nodes.len();
+            let prev = if index == 0 { END } else { index - 1 };
+            let next = if index + 1 == len { END } else { index + 1 };
+            let mut node = Node::new(n.value.clone(), prev, next);
+            node.slot = n.slot;
+            slots[n.slot].node = index;
+            if nodes.push(node).is_err() {
+                panic!("List: the storage is full");
+            }
+        }
+        List {
+            link: if len == 0 { [END, END] } else { [0, len - 1] },
+            nodes,
+            slots,
+            free: self.free,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<'a, T> FromIterator<T> for List<T>
+impl<T, L: Storage<Slot>> List<T, Vec<Node<T>>, L>
 {
-    fn from_iter<I>(iter: I) -> Self
-        where I: IntoIterator<Item=T>
+    /// Convert each element using **f**, keeping the layout of the List.
+    ///
+    /// Handles to elements of this List are valid for the corresponding
+    /// elements of the new List.
+    ///
+    /// **f** is called for the elements in storage order, like *values*.
+    pub fn map<U, F>(self, mut f: F) -> List<U, Vec<Node<U>>, L>
+        where F: FnMut(T) -> U
     {
-        let mut result = List::new();
-        result.extend(iter);
-        result
+        match self.try_map(move |value| Ok::<_, Infallible>(f(value))) {
+            Ok(list) => list,
+            Err(never) => match never { },
+        }
+    }
+
+    /// Convert each element using **f**, keeping the layout of the List,
+    /// and stop at the first error.
+    ///
+    /// Handles to elements of this List are valid for the corresponding
+    /// elements of the new List.
+    ///
+    /// **f** is called for the elements in storage order, like *values*.
+    pub fn try_map<U, E, F>(self, mut f: F) -> Result<List<U, Vec<Node<U>>, L>, E>
+        where F: FnMut(T) -> Result<U, E>
+    {
+        let nodes = self.nodes.into_iter().map(|n| {
+            Ok(Node {
+                value: f(n.value)?,
+                link: n.link,
+                slot: n.slot,
+            })
+        }).collect::<Result<_, E>>()?;
+        Ok(List {
+            link: self.link,
+            nodes,
+            slots: self.slots,
+            free: self.free,
+            marker: PhantomData,
+        })
     }
 }
 
-impl<'a, T> Extend<T> for List<T>
+impl<T: Clone, S: Storage<Node<T>> + Clone, L: Storage<Slot> + Clone> Clone for List<T, S, L>
 {
-    fn extend<I>(&mut self, iter: I) where I: IntoIterator<Item=T>
+    fn clone(&self) -> Self
     {
-        let mut iter = iter.into_iter();
-        let (low, _) = iter.size_hint();
-        self.nodes.reserve(low);
-        let tail = self.tail();
-        let index = self.nodes.len();
-
-        // pick the first to set prev to tail
-        for elt in iter.by_ref() {
-            let node = Node::new(elt, tail, index + 1);
-            self.nodes.push(node);
-            break;
+        List {
+            link: self.link,
+            nodes: self.nodes.clone(),
+            slots: self.slots.clone(),
+            free: self.free,
+            marker: PhantomData,
         }
+    }
 
-        for (i, elt) in iter.enumerate() {
-            let node = Node::new(elt, index + i, index + i + 2);
-            self.nodes.push(node);
-        }
+    /// Reuse the allocations of **self**.
+    fn clone_from(&mut self, source: &Self)
+    {
+        self.link = source.link;
+        self.nodes.clone_from(&source.nodes);
+        self.slots.clone_from(&source.slots);
+        self.free = source.free;
+    }
+}
 
-        if self.nodes.len() == 0 {
-            return;
+/// Skip **n** of the **len** elements between the **link** ends, from the
+/// end **term**.
+///
+/// The new end is found by walking from whichever end is closer to it.
+fn skip_links<T>(nodes: &[Node<T>], link: &mut [usize; 2], len: &mut usize,
+                 n: usize, term: Terminal)
+{
+    if n >= *len {
+        *link = [END, END];
+        *len = 0;
+        return;
+    }
+    let h = term.index();
+    let t = term.opposite().index();
+    let mut index;
+    if n <= *len - 1 - n {
+        index = link[h];
+        for _ in 0..n {
+            index = nodes[index].link[t];
         }
-
-        match self.nodes.get_mut(self.link[1]) {
-            None => self.link[0] = index, // List was empty
-            Some(tailn) => tailn.set_next(index),
+    } else {
+        index = link[t];
+        for _ in 0..*len - 1 - n {
+            index = nodes[index].link[h];
         }
-        self.link[1] = self.nodes.len() - 1;
-        self.nodes[self.link[1]].set_next(END);
+    }
+    link[h] = index;
+    *len -= n;
+}
+
+/// Return the handle for the slot at **index**.
+fn make_handle(slots: &[Slot], index: usize) -> Handle
+{
+    Handle { slot: index, gen: slots[index].gen }
+}
+
+/// Return mutable references to two distinct elements of **slc**.
+fn index_twice<T>(slc: &mut [T], a: usize, b: usize) -> (&mut T, &mut T)
+{
+    assert!(a != b);
+    if a < b {
+        let (fst, snd) = slc.split_at_mut(b);
+        (&mut fst[a], &mut snd[0])
+    } else {
+        let (fst, snd) = slc.split_at_mut(a);
+        (&mut snd[0], &mut fst[b])
+    }
+}
+
+impl<T, S, L> Default for List<T, S, L>
+    where S: Storage<Node<T>> + Default,
+          L: Storage<Slot> + Default,
+{
+    fn default() -> Self { List::with_storages(S::default(), L::default()) }
+}
+
+impl<T, S, L> FromIterator<T> for List<T, S, L>
+    where S: Storage<Node<T>> + Default,
+          L: Storage<Slot> + Default,
+{
+    fn from_iter<I>(iter: I) -> Self
+        where I: IntoIterator<Item=T>
+    {
+        let mut result = List::default();
+        result.extend(iter);
+        result
+    }
+}
+
+impl<T, S: Storage<Node<T>>, L: Storage<Slot>> Extend<T> for List<T, S, L>
+{
+    fn extend<I>(&mut self, iter: I) where I: IntoIterator<Item=T>
+    {
+        self.link_iter_before(END, iter);
     }
 }
 
 impl<'a, T: 'a> Iter<'a, T>
 {
+    /// Pair each element with its handle.
+    pub fn with_handles(self) -> IterWithHandles<'a, T>
+    {
+        IterWithHandles { iter: self }
+    }
+
+    /// Return an iterator of each pair of neighbors, e.g. each element
+    /// together with the one after it.
+    pub fn pairs(self) -> Pairs<'a, T>
+    {
+        Pairs { windows: Windows { iter: self } }
+    }
+
+    /// Skip **n** elements from the head or tail
+    fn skip_terminal(&mut self, n: usize, term: Terminal)
+    {
+        skip_links(self.nodes, &mut self.link, &mut self.len, n, term)
+    }
+
     /// Step the iterator from the head or tail
     fn next_terminal(&mut self, term: Terminal) -> Option<&'a T>
+    {
+        self.next_node(term).map(|n| &n.value)
+    }
+
+    /// Step the iterator from the head or tail, returning the node
+    fn next_node(&mut self, term: Terminal) -> Option<&'a Node<T>>
     {
         let h = term.index();
         let t = term.opposite().index();
@@ -395,8 +1563,8 @@ impl<'a, T: 'a> Iter<'a, T>
             None => None,
             Some(n) => {
                 // Extract `elt` already here, to avoid spurious null check for elt
-                let elt = Some(&n.value);
-                self.taken += 1;
+                let elt = Some(n);
+                self.len -= 1;
                 if self.link[h] == self.link[t] {
                     self.link[0] = END;
                     self.link[1] = END;
@@ -409,6 +1577,13 @@ impl<'a, T: 'a> Iter<'a, T>
     }
 }
 
+impl<'a, T: 'a> Copy for Iter<'a, T> { }
+
+impl<'a, T: 'a> Clone for Iter<'a, T>
+{
+    fn clone(&self) -> Self { *self }
+}
+
 impl<'a, T: 'a> Iterator for Iter<'a, T>
 {
     type Item = &'a T;
@@ -418,8 +1593,17 @@ impl<'a, T: 'a> Iterator for Iter<'a, T>
 
     fn size_hint(&self) -> (usize, Option<usize>)
     {
-        let len = self.nodes.len() - self.taken;
-        (len, Some(len))
+        (self.len, Some(self.len))
+    }
+
+    fn count(self) -> usize { self.len }
+
+    fn last(mut self) -> Option<&'a T> { self.next_back() }
+
+    fn nth(&mut self, n: usize) -> Option<&'a T>
+    {
+        self.skip_terminal(n, Terminal::Head);
+        self.next()
     }
 }
 
@@ -427,13 +1611,50 @@ impl<'a, T: 'a> DoubleEndedIterator for Iter<'a, T>
 {
     #[inline]
     fn next_back(&mut self) -> Option<&'a T> { self.next_terminal(Terminal::Tail) }
+
+    fn nth_back(&mut self, n: usize) -> Option<&'a T>
+    {
+        self.skip_terminal(n, Terminal::Tail);
+        self.next_back()
+    }
 }
 
+impl<'a, T: 'a> ExactSizeIterator for Iter<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for Iter<'a, T> { }
+
 
 impl<'a, T: 'a> IterMut<'a, T>
 {
+    /// Pair each element with its handle.
+    pub fn with_handles(self) -> IterMutWithHandles<'a, T>
+    {
+        IterMutWithHandles { iter: self }
+    }
+
+    /// Skip **n** elements from the head or tail
+    fn skip_terminal(&mut self, n: usize, term: Terminal)
+    {
+        skip_links(self.nodes, &mut self.link, &mut self.len, n, term)
+    }
+
     /// Step the iterator from the head or tail
     fn next_terminal(&mut self, term: Terminal) -> Option<&'a mut T>
+    {
+        self.next_slot(term).map(|(_, elt)| elt)
+    }
+
+    /// Step the iterator from the head or tail, returning the element and
+    /// its handle
+    fn next_entry(&mut self, term: Terminal) -> Option<(Handle, &'a mut T)>
+    {
+        let slots = self.slots;
+        self.next_slot(term).map(|(slot, elt)| (make_handle(slots, slot), elt))
+    }
+
+    /// Step the iterator from the head or tail, returning the element and
+    /// the index of its slot
+    fn next_slot(&mut self, term: Terminal) -> Option<(usize, &'a mut T)>
     {
         let h = term.index();
         let t = term.opposite().index();
@@ -450,9 +1671,9 @@ impl<'a, T: 'a> IterMut<'a, T>
                 let long_life_value = unsafe {
                     &mut *(&mut n.value as *mut _)
                 };
-                let elt = Some(long_life_value);
+                let elt = Some((n.slot, long_life_value));
 
-                self.taken += 1;
+                self.len -= 1;
                 if self.link[h] == self.link[t] {
                     self.link = [END, END];
                 } else {
@@ -473,8 +1694,17 @@ impl<'a, T: 'a> Iterator for IterMut<'a, T>
 
     fn size_hint(&self) -> (usize, Option<usize>)
     {
-        let len = self.nodes.len() - self.taken;
-        (len, Some(len))
+        (self.len, Some(self.len))
+    }
+
+    fn count(self) -> usize { self.len }
+
+    fn last(mut self) -> Option<&'a mut T> { self.next_back() }
+
+    fn nth(&mut self, n: usize) -> Option<&'a mut T>
+    {
+        self.skip_terminal(n, Terminal::Head);
+        self.next()
     }
 }
 
@@ -482,85 +1712,1004 @@ impl<'a, T: 'a> DoubleEndedIterator for IterMut<'a, T>
 {
     #[inline]
     fn next_back(&mut self) -> Option<&'a mut T> { self.next_terminal(Terminal::Tail) }
-}
 
-impl<'a, T: 'a> Cursor<'a, T>
-{
-    /// Step the cursor forward.
-    /// 
-    /// Returns **None** after the last element. After that, another call to
-    /// *.next()* returns the first element of the list.
-    pub fn next(&mut self) -> Option<&mut T>
+    fn nth_back(&mut self, n: usize) -> Option<&'a mut T>
     {
-        match self.list.nodes.get_mut(self.pos) {
-            None => {
-                self.pos = self.list.link[0];
-                None
-            }
-            Some(n) => {
-                self.pos = n.next();
-                Some(&mut n.value)
-            }
-        }
+        self.skip_terminal(n, Terminal::Tail);
+        self.next_back()
     }
+}
 
-    /// Step the cursor backward.
-    ///
-    /// Returns **None** when positioned before the first element. After that,
-    /// another call to *.prev()* returns the last element of the list.
-    pub fn prev(&mut self) -> Option<&mut T>
+impl<'a, T: 'a> ExactSizeIterator for IterMut<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for IterMut<'a, T> { }
+
+/// An iterator that visits each element once, starting anywhere in the
+/// **List** and wrapping around; created by *List::cycle_from*.
+#[derive(Debug)]
+pub struct Cycle<'a, T: 'a>
+{
+    /// The elements left, from the front to the back; an end that steps
+    /// off the list is re-seeded from **ends**.
+    iter: Iter<'a, T>,
+    /// Head and tail of the list
+    ends: [usize; 2],
+}
+
+impl<'a, T: 'a> Clone for Cycle<'a, T>
+{
+    fn clone(&self) -> Self { Cycle { ..*self } }
+}
+
+impl<'a, T: 'a> Cycle<'a, T>
+{
+    /// Step the iterator from the head or tail, wrapping around at the ends
+    /// of the list.
+    fn next_node(&mut self, term: Terminal) -> Option<&'a Node<T>>
     {
-        if self.pos == self.list.head() {
-            // jump back from head to one past the end, just like gankro's cursor
-            self.pos = END;
+        if self.iter.len == 0 {
             return None;
         }
-        let prev = 
-            match self.list.nodes.get(self.pos) {
-                None => self.list.tail(),
-                Some(n) => n.prev(),
-            };
-        match self.list.nodes.get_mut(prev) {
-            None => None,
-            Some(n) => {
-                self.pos = prev;
-                Some(&mut n.value)
-            }
+        let h = term.index();
+        if self.iter.link[h] == END {
+            self.iter.link[h] = self.ends[h];
         }
+        self.iter.next_node(term)
     }
+}
 
-    /// Insert an element at the current position, e.g. before the element
-    /// that would be returned by *.next()* in this position.
-    pub fn insert(&mut self, value: T)
+impl<'a, T: 'a> Iterator for Cycle<'a, T>
+{
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T>
     {
-        let index = self.list.len();
-        if self.pos == END {
-            self.list.push_back(value);
-            self.pos = index;
-        } else if self.pos == self.list.head() {
-            self.list.push_front(value);
-            self.pos = index;
-        } else {
-            let prev = self.list.nodes[self.pos].prev();
-            let node = Node::new(value, prev, self.pos);
+        self.next_node(Terminal::Head).map(|n| &n.value)
+    }
 
-            match self.list.nodes.get_mut(prev) {
-                None => self.list.link[0] = index, // prev is END
-                Some(n) => n.set_next(index),
-            }
-            self.list.nodes[self.pos].set_prev(index);
-            self.list.nodes.push(node);
-            self.pos = index;
-        }
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        self.iter.size_hint()
     }
+}
 
-    pub fn seek(&mut self, offset: Seek)
+impl<'a, T: 'a> DoubleEndedIterator for Cycle<'a, T>
+{
+    fn next_back(&mut self) -> Option<&'a T>
     {
-        match offset {
-            Seek::Head => self.pos = self.list.head(),
-            Seek::Tail => self.pos = END,
-            Seek::Forward(n) => for _ in 0..n { if self.pos == END { break; } self.next(); },
-            Seek::Backward(n) => for _ in 0..n { if self.pos == self.list.head() { break; } self.prev(); }
+        self.next_node(Terminal::Tail).map(|n| &n.value)
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Cycle<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for Cycle<'a, T> { }
+
+/// An iterator that cycles through the elements endlessly, starting
+/// anywhere in the **List**; created by *List::cycle_forever_from*.
+#[derive(Debug)]
+pub struct CycleForever<'a, T: 'a>
+{
+    cycle: Cycle<'a, T>,
+}
+
+impl<'a, T: 'a> Clone for CycleForever<'a, T>
+{
+    fn clone(&self) -> Self { CycleForever { cycle: self.cycle.clone() } }
+}
+
+impl<'a, T: 'a> CycleForever<'a, T>
+{
+    /// Step the iterator from the head or tail; after a full round, start
+    /// the next one from where this one ended.
+    fn step_terminal(&mut self, term: Terminal) -> Option<&'a T>
+    {
+        let h = term.index();
+        let t = term.opposite().index();
+        let pos = match self.cycle.iter.link[h] {
+            END => self.cycle.ends[h],
+            pos => pos,
+        };
+        let n = self.cycle.next_node(term)?;
+        let iter = &mut self.cycle.iter;
+        if iter.len == 0 {
+            iter.link[h] = n.link[t];
+            iter.link[t] = pos;
+            iter.len = iter.nodes.len();
+        }
+        Some(&n.value)
+    }
+}
+
+impl<'a, T: 'a> Iterator for CycleForever<'a, T>
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> { self.step_terminal(Terminal::Head) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (usize::MAX, None) }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for CycleForever<'a, T>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> { self.step_terminal(Terminal::Tail) }
+}
+
+impl<'a, T: 'a> FusedIterator for CycleForever<'a, T> { }
+
+/// An iterator of runs of **N** consecutive elements, created by
+/// *List::windows*.
+#[derive(Debug)]
+pub struct Windows<'a, T: 'a, const N: usize>
+{
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T: 'a, const N: usize> Clone for Windows<'a, T, N>
+{
+    fn clone(&self) -> Self { Windows { iter: self.iter } }
+}
+
+impl<'a, T: 'a, const N: usize> Windows<'a, T, N>
+{
+    /// Return the window that starts at **index**
+    fn window(&self, mut index: usize) -> [&'a T; N]
+    {
+        let nodes = self.iter.nodes;
+        array::from_fn(|_| {
+            let n = &nodes[index];
+            index = n.next();
+            &n.value
+        })
+    }
+}
+
+impl<'a, T: 'a, const N: usize> Iterator for Windows<'a, T, N>
+{
+    type Item = [&'a T; N];
+
+    fn next(&mut self) -> Option<[&'a T; N]>
+    {
+        if self.iter.len < N {
+            return None;
+        }
+        let window = self.window(self.iter.link[0]);
+        self.iter.next();
+        Some(window)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>)
+    {
+        let len = (self.iter.len + 1).saturating_sub(N);
+        (len, Some(len))
+    }
+}
+
+impl<'a, T: 'a, const N: usize> DoubleEndedIterator for Windows<'a, T, N>
+{
+    fn next_back(&mut self) -> Option<[&'a T; N]>
+    {
+        if self.iter.len < N {
+            return None;
+        }
+        let mut start = self.iter.link[1];
+        for _ in 1..N {
+            start = self.iter.nodes[start].prev();
+        }
+        let window = self.window(start);
+        self.iter.next_back();
+        Some(window)
+    }
+}
+
+impl<'a, T: 'a, const N: usize> ExactSizeIterator for Windows<'a, T, N> { }
+
+impl<'a, T: 'a, const N: usize> FusedIterator for Windows<'a, T, N> { }
+
+/// An iterator of each pair of neighbors, created by *Iter::pairs*.
+#[derive(Debug)]
+pub struct Pairs<'a, T: 'a>
+{
+    windows: Windows<'a, T, 2>,
+}
+
+impl<'a, T: 'a> Clone for Pairs<'a, T>
+{
+    fn clone(&self) -> Self { Pairs { windows: self.windows.clone() } }
+}
+
+impl<'a, T: 'a> Iterator for Pairs<'a, T>
+{
+    type Item = (&'a T, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(&'a T, &'a T)> { self.windows.next().map(|[a, b]| (a, b)) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.windows.size_hint() }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for Pairs<'a, T>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<(&'a T, &'a T)> { self.windows.next_back().map(|[a, b]| (a, b)) }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Pairs<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for Pairs<'a, T> { }
+
+/// An iterator of a **List**'s elements in storage order, created by
+/// *List::values*.
+#[derive(Debug)]
+pub struct Values<'a, T: 'a>
+{
+    iter: slice::Iter<'a, Node<T>>,
+}
+
+impl<'a, T: 'a> Clone for Values<'a, T>
+{
+    fn clone(&self) -> Self { Values { iter: self.iter.clone() } }
+}
+
+impl<'a, T: 'a> Iterator for Values<'a, T>
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> { self.iter.next().map(|n| &n.value) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+        where F: FnMut(B, &'a T) -> B
+    {
+        self.iter.fold(init, move |acc, n| f(acc, &n.value))
+    }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for Values<'a, T>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> { self.iter.next_back().map(|n| &n.value) }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Values<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for Values<'a, T> { }
+
+/// An iterator of mutable references to a **List**'s elements in storage
+/// order, created by *List::values_mut*.
+#[derive(Debug)]
+pub struct ValuesMut<'a, T: 'a>
+{
+    iter: slice::IterMut<'a, Node<T>>,
+}
+
+impl<'a, T: 'a> Iterator for ValuesMut<'a, T>
+{
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> { self.iter.next().map(|n| &mut n.value) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+        where F: FnMut(B, &'a mut T) -> B
+    {
+        self.iter.fold(init, move |acc, n| f(acc, &mut n.value))
+    }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for ValuesMut<'a, T>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> { self.iter.next_back().map(|n| &mut n.value) }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for ValuesMut<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for ValuesMut<'a, T> { }
+
+/// An iterator of the handles of a **List**'s elements, created by
+/// *List::handles*.
+#[derive(Debug)]
+pub struct Handles<'a, T: 'a>
+{
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T: 'a> Clone for Handles<'a, T>
+{
+    fn clone(&self) -> Self { Handles { iter: self.iter } }
+}
+
+impl<'a, T: 'a> Iterator for Handles<'a, T>
+{
+    type Item = Handle;
+
+    #[inline]
+    fn next(&mut self) -> Option<Handle>
+    {
+        self.iter.next_node(Terminal::Head).map(|n| make_handle(self.iter.slots, n.slot))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for Handles<'a, T>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Handle>
+    {
+        self.iter.next_node(Terminal::Tail).map(|n| make_handle(self.iter.slots, n.slot))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for Handles<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for Handles<'a, T> { }
+
+/// An iterator of elements and their handles, created by *Iter::with_handles*.
+#[derive(Debug)]
+pub struct IterWithHandles<'a, T: 'a>
+{
+    iter: Iter<'a, T>,
+}
+
+impl<'a, T: 'a> Clone for IterWithHandles<'a, T>
+{
+    fn clone(&self) -> Self { IterWithHandles { iter: self.iter } }
+}
+
+impl<'a, T: 'a> Iterator for IterWithHandles<'a, T>
+{
+    type Item = (Handle, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(Handle, &'a T)>
+    {
+        self.iter.next_node(Terminal::Head)
+            .map(|n| (make_handle(self.iter.slots, n.slot), &n.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for IterWithHandles<'a, T>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<(Handle, &'a T)>
+    {
+        self.iter.next_node(Terminal::Tail)
+            .map(|n| (make_handle(self.iter.slots, n.slot), &n.value))
+    }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for IterWithHandles<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for IterWithHandles<'a, T> { }
+
+/// An iterator of mutable elements and their handles, created by
+/// *IterMut::with_handles*.
+#[derive(Debug)]
+pub struct IterMutWithHandles<'a, T: 'a>
+{
+    iter: IterMut<'a, T>,
+}
+
+impl<'a, T: 'a> Iterator for IterMutWithHandles<'a, T>
+{
+    type Item = (Handle, &'a mut T);
+
+    #[inline]
+    fn next(&mut self) -> Option<(Handle, &'a mut T)> { self.iter.next_entry(Terminal::Head) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { self.iter.size_hint() }
+}
+
+impl<'a, T: 'a> DoubleEndedIterator for IterMutWithHandles<'a, T>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<(Handle, &'a mut T)> { self.iter.next_entry(Terminal::Tail) }
+}
+
+impl<'a, T: 'a> ExactSizeIterator for IterMutWithHandles<'a, T> { }
+
+impl<'a, T: 'a> FusedIterator for IterMutWithHandles<'a, T> { }
+
+impl<'a, T: 'a> SubList<'a, T>
+{
+    /// Return the number of elements in the SubList.
+    pub fn len(&self) -> usize
+    {
+        self.len
+    }
+
+    /// Return **true** if the SubList is empty.
+    pub fn is_empty(&self) -> bool
+    {
+        self.len == 0
+    }
+
+    /// Return an iterator.
+    pub fn iter(&self) -> Iter<'a, T>
+    {
+        Iter {
+            link: self.link,
+            nodes: self.nodes,
+            slots: self.slots,
+            len: self.len,
+        }
+    }
+
+    /// Return a new cursor, positioned at the first element of the SubList.
+    pub fn cursor(&self) -> SubCursor<'a, T>
+    {
+        SubCursor {
+            pos: self.link[0],
+            index: 0,
+            list: *self,
+        }
+    }
+}
+
+impl<'a, T: 'a> Copy for SubList<'a, T> { }
+
+impl<'a, T: 'a> Clone for SubList<'a, T>
+{
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T: 'a> IntoIterator for SubList<'a, T>
+{
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+    fn into_iter(self) -> Iter<'a, T> { self.iter() }
+}
+
+impl<'a, T: 'a> Copy for SubCursor<'a, T> { }
+
+impl<'a, T: 'a> Clone for SubCursor<'a, T>
+{
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T: 'a> SubCursor<'a, T>
+{
+    /// Return the element at the current position, or **None** if the
+    /// cursor is at the end.
+    pub fn current(&self) -> Option<&'a T>
+    {
+        self.list.nodes.get(self.pos).map(|n| &n.value)
+    }
+
+    /// Move the cursor to the next position, wrapping around from the end
+    /// to the first element.
+    ///
+    /// Return **true** if the cursor moved, which is unless the SubList is
+    /// empty.
+    pub fn move_next(&mut self) -> bool
+    {
+        if self.list.len == 0 {
+            return false;
+        }
+        if self.pos == END {
+            self.pos = self.list.link[0];
+            self.index = 0;
+        } else if self.pos == self.list.link[1] {
+            self.pos = END;
+            self.index += 1;
+        } else {
+            self.pos = self.list.nodes[self.pos].next();
+            self.index += 1;
+        }
+        true
+    }
+
+    /// Move the cursor to the previous position, wrapping around from
+    /// the first element to the end.
+    ///
+    /// Return **true** if the cursor moved, which is unless the SubList is
+    /// empty.
+    pub fn move_prev(&mut self) -> bool
+    {
+        if self.list.len == 0 {
+            return false;
+        }
+        if self.pos == END {
+            self.pos = self.list.link[1];
+            self.index = self.list.len - 1;
+        } else if self.pos == self.list.link[0] {
+            self.pos = END;
+            self.index = self.list.len;
+        } else {
+            self.pos = self.list.nodes[self.pos].prev();
+            self.index -= 1;
+        }
+        true
+    }
+
+    /// Step the cursor forward, returning the element at the position it
+    /// leaves, like *Cursor::next*.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&'a T>
+    {
+        let elt = self.current();
+        self.move_next();
+        elt
+    }
+
+    /// Step the cursor backward, returning the element at the position it
+    /// arrives at, like *Cursor::prev*.
+    pub fn prev(&mut self) -> Option<&'a T>
+    {
+        if self.pos == self.list.link[0] {
+            self.move_prev();
+            return None;
+        }
+        self.move_prev();
+        self.current()
+    }
+
+    /// Return the index of the element at the current position in the
+    /// SubList, or **None** if the cursor is at the end.
+    pub fn index(&self) -> Option<usize>
+    {
+        if self.pos == END {
+            None
+        } else {
+            Some(self.index)
+        }
+    }
+}
+
+impl<'a, T: 'a, S: Storage<Node<T>> + 'a> Drain<'a, T, S>
+{
+    /// Take the node at the head or tail of the run, and move the last node
+    /// of the storage, which is also in the run, into its place.
+    fn next_terminal(&mut self, term: Terminal) -> Option<T>
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let h = term.index();
+        let t = term.opposite().index();
+        let index = self.link[h];
+        let after = self.nodes[index].link[t];
+        self.link[h] = after;
+        if let Some(n) = self.nodes.get_mut(after) {
+            n.link[h] = END;
+        }
+        self.len -= 1;
+
+        let node = self.nodes.swap_remove(index);
+        if let Some(moved) = self.nodes.get(index) {
+            let link = moved.link;
+            for (i, &l) in link.iter().enumerate() {
+                match self.nodes.get_mut(l) {
+                    None => self.link[i] = index,
+                    Some(n) => n.link[1 - i] = index,
+                }
+            }
+        }
+        Some(node.value)
+    }
+}
+
+impl<'a, T: 'a, S: Storage<Node<T>> + 'a> Iterator for Drain<'a, T, S>
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> { self.next_terminal(Terminal::Head) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.len, Some(self.len)) }
+}
+
+impl<'a, T: 'a, S: Storage<Node<T>> + 'a> DoubleEndedIterator for Drain<'a, T, S>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<T> { self.next_terminal(Terminal::Tail) }
+}
+
+impl<'a, T: 'a, S: Storage<Node<T>> + 'a> ExactSizeIterator for Drain<'a, T, S> { }
+
+impl<'a, T: 'a, S: Storage<Node<T>> + 'a> FusedIterator for Drain<'a, T, S> { }
+
+impl<'a, T: 'a, S: Storage<Node<T>> + 'a> Drop for Drain<'a, T, S>
+{
+    fn drop(&mut self)
+    {
+        // The rest of the run is at the end of the storage
+        let new_len = self.nodes.len() - self.len;
+        self.nodes.truncate(new_len);
+    }
+}
+
+impl<'a, T: 'a, S: Storage<Node<T>> + 'a, L: Storage<Slot> + 'a> Cursor<'a, T, S, L>
+{
+    /// Return the element at the current position, or **None** if the
+    /// cursor is at the end.
+    pub fn current(&mut self) -> Option<&mut T>
+    {
+        self.list.nodes.get_mut(self.pos).map(|n| &mut n.value)
+    }
+
+    /// Move the cursor to the next position.
+    ///
+    /// From the last element it moves to the end. From the end, it moves to
+    /// the first element if the cursor is wrapping, else it stays.
+    ///
+    /// Return **true** if the cursor moved.
+    pub fn move_next(&mut self) -> bool
+    {
+        match self.list.nodes.get(self.pos) {
+            None => {
+                if !self.wrap || self.list.is_empty() {
+                    return false;
+                }
+                self.pos = self.list.head();
+                self.index = Some(0);
+            }
+            Some(n) => {
+                self.pos = n.next();
+                self.index = self.index.map(|i| i + 1);
+            }
+        }
+        true
+    }
+
+    /// Move the cursor to the previous position.
+    ///
+    /// From the end it moves to the last element. From the first element, it
+    /// moves to the end if the cursor is wrapping, else it stays.
+    ///
+    /// Return **true** if the cursor moved.
+    pub fn move_prev(&mut self) -> bool
+    {
+        if self.pos == self.list.head() {
+            if !self.wrap || self.pos == END {
+                return false;
+            }
+            // jump back from head to one past the end, just like gankro's cursor
+            self.pos = END;
+            self.index = Some(self.list.len());
+            return true;
+        }
+        self.pos = match self.list.nodes.get(self.pos) {
+            None => {
+                self.index = Some(self.list.len());
+                self.list.tail()
+            }
+            Some(n) => n.prev(),
+        };
+        self.index = self.index.map(|i| i - 1);
+        true
+    }
+
+    /// Set whether the cursor wraps around between the end and the
+    /// first element. Cursors start out wrapping.
+    pub fn set_wrapping(&mut self, wrap: bool)
+    {
+        self.wrap = wrap;
+    }
+
+    /// Return **true** if the cursor wraps around.
+    pub fn is_wrapping(&self) -> bool
+    {
+        self.wrap
+    }
+
+    /// Step the cursor forward, returning the element at the position it
+    /// leaves.
+    /// 
+    /// Returns **None** at the end. After that, another call to *.next()*
+    /// returns the first element of the list, unless the cursor is not
+    /// wrapping.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&mut T>
+    {
+        let pos = self.pos;
+        self.move_next();
+        self.list.nodes.get_mut(pos).map(|n| &mut n.value)
+    }
+
+    /// Step the cursor backward, returning the element at the position it
+    /// arrives at.
+    ///
+    /// Returns **None** when stepping back from the first element, and the
+    /// cursor moves to the end. After that, another call to *.prev()* returns
+    /// the last element of the list. If the cursor is not wrapping, it
+    /// instead stays at the first element.
+    pub fn prev(&mut self) -> Option<&mut T>
+    {
+        if self.pos == self.list.head() {
+            self.move_prev();
+            return None;
+        }
+        self.move_prev();
+        self.current()
+    }
+
+    /// Insert an element at the current position, e.g. before the element
+    /// that would be returned by *.next()* in this position.
+    pub fn insert(&mut self, value: T)
+    {
+        self.list.reserve_node();
+        self.link_before(value);
+    }
+
+    /// Insert an element at the current position, like *.insert()*, or
+    /// return it in the error if there is no room. The List is unchanged
+    /// on error.
+    pub fn try_insert(&mut self, value: T) -> Result<(), TryPushError<T>>
+    {
+        if let Err(error) = self.list.try_reserve_node() {
+            return Err(TryPushError { value, error });
+        }
+        self.link_before(value);
+        Ok(())
+    }
+
+    /// Insert a node with **value** before the current position and move
+    /// to it; there must be room.
+    fn link_before(&mut self, value: T)
+    {
+        let index = self.list.len();
+        if self.pos == END {
+            self.list.link_terminal(value, Terminal::Tail);
+            self.pos = index;
+        } else if self.pos == self.list.head() {
+            self.list.link_terminal(value, Terminal::Head);
+            self.pos = index;
+        } else {
+            let prev = self.list.nodes[self.pos].prev();
+            let node = Node::new(value, prev, self.pos);
+
+            match self.list.nodes.get_mut(prev) {
+                None => self.list.link[0] = index, // prev is END
+                Some(n) => n.set_next(index),
+            }
+            self.list.nodes[self.pos].set_prev(index);
+            self.list.push_node(node);
+            self.pos = index;
+        }
+    }
+
+    /// Insert an element after the current position, e.g. after the element
+    /// that would be returned by *.next()* in this position, or at the
+    /// beginning of the list if the cursor is at the end.
+    ///
+    /// The cursor does not move.
+    pub fn insert_after(&mut self, value: T)
+    {
+        let index = self.list.len();
+        match self.list.nodes.get(self.pos) {
+            None => {
+                self.list.push_front(value);
+                self.index = self.index.map(|i| i + 1);
+            }
+            Some(_) => {
+                self.list.reserve_node();
+                let next = self.list.nodes[self.pos].next();
+                let node = Node::new(value, self.pos, next);
+                match self.list.nodes.get_mut(next) {
+                    None => self.list.link[1] = index, // next is END
+                    Some(n) => n.set_prev(index),
+                }
+                self.list.nodes[self.pos].set_next(index);
+                self.list.push_node(node);
+            }
+        }
+    }
+
+    /// Insert all elements of **iter** at the current position, in order,
+    /// e.g. before the element that would be returned by *.next()*.
+    ///
+    /// The cursor is positioned at the first inserted element, just like
+    /// after *.insert()*.
+    pub fn insert_iter<I>(&mut self, iter: I)
+        where I: IntoIterator<Item=T>
+    {
+        self.pos = self.list.link_iter_before(self.pos, iter);
+    }
+
+    /// Replace the element at the current position, e.g. the element
+    /// that would be returned by *.next()*, and return the old value.
+    ///
+    /// **Panics** if the cursor is at the end.
+    pub fn replace(&mut self, value: T) -> T
+    {
+        match self.list.nodes.get_mut(self.pos) {
+            None => panic!("Cursor::replace: the cursor is at the end of the list"),
+            Some(n) => mem::replace(&mut n.value, value),
+        }
+    }
+
+    /// Swap the element at the current position with the one after it,
+    /// by relinking the nodes. The cursor stays with the same element, which is
+    /// now one step further ahead.
+    ///
+    /// Return **false**, and do nothing, if there is no element after
+    /// the current position or if the cursor is at the end.
+    pub fn swap_with_next(&mut self) -> bool
+    {
+        let a = self.pos;
+        let b = match self.list.nodes.get(a) {
+            None => return false,
+            Some(n) => n.next(),
+        };
+        if b == END {
+            return false;
+        }
+        let prev = self.list.nodes[a].prev();
+        let next = self.list.nodes[b].next();
+        match self.list.nodes.get_mut(prev) {
+            None => self.list.link[0] = b,
+            Some(n) => n.set_next(b),
+        }
+        match self.list.nodes.get_mut(next) {
+            None => self.list.link[1] = a,
+            Some(n) => n.set_prev(a),
+        }
+        self.list.nodes[b].link = [prev, a];
+        self.list.nodes[a].link = [b, next];
+        self.index = self.index.map(|i| i + 1);
+        true
+    }
+
+    /// Step the cursor forward until it has passed an element that satisfies
+    /// **f**, and return that element, like repeated calls to *.next()*.
+    ///
+    /// Returns **None** if no element up to the end of the list matched;
+    /// the cursor is then at the end. It does not wrap around.
+    pub fn find_next<F>(&mut self, mut f: F) -> Option<&mut T>
+        where F: FnMut(&T) -> bool
+    {
+        while let Some(n) = self.list.nodes.get(self.pos) {
+            let index = self.pos;
+            self.pos = n.next();
+            self.index = self.index.map(|i| i + 1);
+            if f(&n.value) {
+                return Some(&mut self.list.nodes[index].value);
+            }
+        }
+        None
+    }
+
+    /// Step the cursor backward until it reaches an element that satisfies
+    /// **f**, and return that element, like repeated calls to *.prev()*.
+    ///
+    /// Returns **None** if no element back to the beginning of the list
+    /// matched; the cursor is then at the beginning. It does not wrap around.
+    pub fn find_prev<F>(&mut self, mut f: F) -> Option<&mut T>
+        where F: FnMut(&T) -> bool
+    {
+        while self.pos != self.list.head() {
+            let prev = 
+                match self.list.nodes.get(self.pos) {
+                    None => self.list.tail(),
+                    Some(n) => n.prev(),
+                };
+            self.pos = prev;
+            self.index = self.index.map(|i| i - 1);
+            if f(&self.list.nodes[prev].value) {
+                return Some(&mut self.list.nodes[prev].value);
+            }
+        }
+        None
+    }
+
+    /// Remove up to **n** elements, starting with the element at the
+    /// current position. The cursor moves to the element after them.
+    ///
+    /// Return the number of elements removed.
+    pub fn remove_n(&mut self, n: usize) -> usize
+    {
+        let (removed, after) = self.list.cut_run(self.pos, n);
+        let new_len = self.list.nodes.len() - removed;
+        self.list.nodes.truncate(new_len);
+        self.pos = after;
+        removed
+    }
+
+    /// Remove up to **n** elements, starting with the element at the
+    /// current position, and return them in an iterator. The cursor moves
+    /// to the element after them.
+    pub fn drain_forward(&mut self, n: usize) -> Drain<'_, T, S>
+    {
+        let (drain, after) = self.list.drain_run(self.pos, n);
+        self.pos = after;
+        drain
+    }
+
+    /// Return the index of the element at the current position, e.g. the
+    /// element that would be returned by *.next()*, or **None** if the
+    /// cursor is at the end.
+    pub fn index(&self) -> Option<usize>
+    {
+        if self.pos == END {
+            None
+        } else {
+            Some(self.ordinal())
+        }
+    }
+
+    /// Return the ordinal position of the cursor, computing it if needed.
+    fn ordinal(&self) -> usize
+    {
+        match self.index {
+            Some(i) => i,
+            None => self.list.ordinal(self.pos),
+        }
+    }
+
+    /// Return a handle to the element at the current position, or **None**
+    /// if the cursor is at the end.
+    ///
+    /// Use *List::cursor_at* to create a new cursor at the same element later.
+    pub fn handle(&self) -> Option<Handle>
+    {
+        if self.pos == END {
+            None
+        } else {
+            Some(self.list.handle(self.pos))
+        }
+    }
+
+    /// Move the cursor according to **offset**.
+    ///
+    /// Return the number of positions the cursor moved, which is less than
+    /// requested if it stopped at the beginning or the end.
+    pub fn seek(&mut self, offset: Seek) -> usize
+    {
+        let mut steps = 0;
+        match offset {
+            Seek::Head => {
+                steps = self.ordinal();
+                self.pos = self.list.head();
+                self.index = Some(0);
+            }
+            Seek::Tail => {
+                steps = self.list.len() - self.ordinal();
+                self.pos = END;
+                self.index = Some(self.list.len());
+            }
+            Seek::Forward(n) => for _ in 0..n { if self.pos == END { break; } self.move_next(); steps += 1; },
+            Seek::Backward(n) => for _ in 0..n { if self.pos == self.list.head() { break; } self.move_prev(); steps += 1; }
+            Seek::To(n) => steps = self.seek_index(n),
+            Seek::FromTail(n) => {
+                let len = self.list.len();
+                steps = self.seek_index(len - n.min(len));
+            }
+        }
+        steps
+    }
+
+    /// Seek to the element at **index**, or to the end, walking from
+    /// the head, the end or the current position, whichever is closest.
+    ///
+    /// Return the number of positions the cursor moved.
+    fn seek_index(&mut self, index: usize) -> usize
+    {
+        let len = self.list.len();
+        let index = index.min(len);
+        let start = self.ordinal();
+        self.index = Some(start);
+        if index < start && index <= start - index {
+            self.seek(Seek::Head);
+        } else if index > start && len - index <= index - start {
+            self.seek(Seek::Tail);
+        }
+        let current = self.ordinal();
+        if index < current {
+            self.seek(Seek::Backward(current - index));
+        } else {
+            self.seek(Seek::Forward(index - current));
         }
+        start.abs_diff(index)
     }
 }
@@ -0,0 +1,628 @@
+//! **RawList** is a doubly linked list in a caller-supplied byte buffer.
+//!
+//! The links are fixed-width indices rather than pointers, so the buffer
+//! can be shared memory or a memory-mapped file, and can be attached to by
+//! another process. Everything is stored in native byte order.
+//!
+//! The buffer starts with a header, followed by the nodes:
+//!
+//! ```ignore
+//! #[repr(C)]
+//! struct RawHeader<Ix> {
+//!     magic: u32,
+//!     /// Size of a node in bytes
+//!     node_size: u32,
+//!     head: Ix,
+//!     tail: Ix,
+//!     len: Ix,
+//! }
+//!
+//! #[repr(C)]
+//! struct RawNode<T, Ix> {
+//!     /// Prev, Next.
+//!     link: [Ix; 2],
+//!     value: T,
+//! }
+//! ```
+//!
+//! The largest value of **Ix** is the “null” link.
+//!
+//! **ArchivedList** is a read-only view of a list in the same format, so
+//! that a **List** can be written to a file once, and then be iterated
+//! directly from the bytes, for example of a memory-mapped file.
+
+use core::fmt;
+use core::iter::FusedIterator;
+use core::mem::{align_of, size_of};
+use core::slice;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use storage::Storage;
+use {List, Node, Slot, Terminal, END};
+
+mod private {
+    pub trait Sealed { }
+}
+
+/// A value that can be stored in a **RawList**.
+///
+/// # Safety
+///
+/// Every bit pattern must be a valid value of the type, because a
+/// **RawList** reads values from arbitrary bytes. The type must not have
+/// padding bytes either: the buffer of a **RawList** is plain bytes, that
+/// the caller may copy or a **DiskList** writes to a file, and writing a
+/// value leaves its padding uninitialized. (The padding of the nodes
+/// themselves is not a problem, since a **RawList** only writes their
+/// fields.) The type should not hold pointers or references, since they
+/// would not be meaningful in another process.
+pub unsafe trait Plain : Copy { }
+
+macro_rules! impl_plain {
+    ($($t:ty)*) => {$(
+        unsafe impl Plain for $t { }
+    )*}
+}
+
+impl_plain!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+
+unsafe impl<T: Plain, const N: usize> Plain for [T; N] { }
+
+/// The integer type of the links of a **RawList**: *u32* or *u64*.
+pub trait RawIndex : Plain + Eq + fmt::Debug + private::Sealed
+{
+    #[doc(hidden)]
+    const END: Self;
+    #[doc(hidden)]
+    const MAGIC: u32;
+    /// The largest capacity, which keeps the length below the null link.
+    #[doc(hidden)]
+    const MAX_LEN: usize;
+    #[doc(hidden)]
+    fn new(index: usize) -> Self;
+    #[doc(hidden)]
+    fn index(self) -> usize;
+}
+
+macro_rules! impl_raw_index {
+    ($($t:ident $magic:expr)*) => {$(
+        impl private::Sealed for $t { }
+
+        impl RawIndex for $t
+        {
+            const END: Self = $t::MAX;
+            const MAGIC: u32 = u32::from_ne_bytes($magic);
+            const MAX_LEN: usize = ($t::MAX - 1) as usize;
+            #[inline]
+            fn new(index: usize) -> Self { index as $t }
+            #[inline]
+            fn index(self) -> usize
+            {
+                if self == Self::END { usize::MAX } else { self as usize }
+            }
+        }
+    )*}
+}
+
+impl_raw_index!(u32 *b"ixl4" u64 *b"ixl8");
+
+/// The error returned when a buffer can not hold or does not hold a valid
+/// **RawList**.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RawError
+{
+    /// The buffer is too small for the header, or for the list's length.
+    TooSmall,
+    /// The buffer is not aligned for the header and nodes.
+    Misaligned,
+    /// The header is not that of a **RawList** with this index type.
+    BadMagic,
+    /// The header's node size does not match the element type.
+    NodeSize,
+    /// The header or the links are not consistent.
+    Corrupt,
+}
+
+impl fmt::Display for RawError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str(match *self {
+            RawError::TooSmall => "the buffer is too small",
+            RawError::Misaligned => "the buffer is misaligned",
+            RawError::BadMagic => "the buffer does not hold a list",
+            RawError::NodeSize => "the buffer holds a list of a different element type",
+            RawError::Corrupt => "the list in the buffer is corrupt",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for RawError { }
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+struct RawHeader<Ix>
+{
+    magic: u32,
+    node_size: u32,
+    head: Ix,
+    tail: Ix,
+    len: Ix,
+}
+
+/// A node of a **RawList**, with a fixed `#[repr(C)]` layout.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct RawNode<T, Ix = u32>
+{
+    /// Prev, Next.
+    pub(crate) link: [Ix; 2],
+    pub value: T,
+}
+
+/// **RawList** is a doubly linked list of **Plain** values in a
+/// caller-supplied byte buffer, with **Ix** links (*u32* by default).
+///
+/// Like **List**, the elements are kept at the start of the node array:
+/// removing an element moves the last one into its place.
+pub struct RawList<'a, T: 'a, Ix: 'a = u32>
+{
+    header: &'a mut RawHeader<Ix>,
+    nodes: &'a mut [RawNode<T, Ix>],
+}
+
+/// **ArchivedList** is a read-only view of a list of **Plain** values in
+/// a byte slice, in the format of **RawList**, with **Ix** links (*u32* by
+/// default).
+///
+/// The bytes are checked once when the view is created, and are then used
+/// in place, without copying or deserializing.
+pub struct ArchivedList<'a, T: 'a, Ix: 'a = u32>
+{
+    header: &'a RawHeader<Ix>,
+    nodes: &'a [RawNode<T, Ix>],
+}
+
+/// An iterator of the elements of a **RawList** or an **ArchivedList**.
+pub struct RawIter<'a, T: 'a, Ix: 'a = u32>
+{
+    link: [Ix; 2],
+    nodes: &'a [RawNode<T, Ix>],
+    /// Number of elements left
+    len: usize,
+}
+
+impl<'a, T: Plain + 'a, Ix: RawIndex + 'a> RawList<'a, T, Ix>
+{
+    /// Return the offset of the first node from the start of the buffer.
+    fn nodes_offset() -> usize
+    {
+        let align = align_of::<RawNode<T, Ix>>();
+        size_of::<RawHeader<Ix>>().div_ceil(align) * align
+    }
+
+    /// Return the alignment the buffer needs.
+    pub fn align() -> usize
+    {
+        align_of::<RawHeader<Ix>>().max(align_of::<RawNode<T, Ix>>())
+    }
+
+    /// Return the size in bytes of a buffer that can hold **capacity**
+    /// elements.
+    ///
+    /// **Panics** if the size overflows `usize`.
+    pub fn size_for(capacity: usize) -> usize
+    {
+        let offset = Self::nodes_offset();
+        capacity.checked_mul(size_of::<RawNode<T, Ix>>())
+            .and_then(|size| size.checked_add(offset))
+            .expect("RawList::size_for: size overflows usize")
+    }
+
+    /// Check that **buf** is aligned and has room for the header, and
+    /// return the number of nodes it can hold.
+    fn check_buffer(buf: &[u8]) -> Result<usize, RawError>
+    {
+        if buf.as_ptr().align_offset(Self::align()) != 0 {
+            return Err(RawError::Misaligned);
+        }
+        let offset = Self::nodes_offset();
+        if buf.len() < offset {
+            return Err(RawError::TooSmall);
+        }
+        Ok(((buf.len() - offset) / size_of::<RawNode<T, Ix>>()).min(Ix::MAX_LEN))
+    }
+
+    /// Split **buf** into the header and the nodes.
+    fn split(buf: &'a mut [u8]) -> Result<Self, RawError>
+    {
+        let cap = Self::check_buffer(buf)?;
+        let (header, nodes) = buf.split_at_mut(Self::nodes_offset());
+        // The buffer is aligned, large enough, and any bytes are valid
+        // values of the header and the nodes.
+        unsafe {
+            Ok(RawList {
+                header: &mut *(header.as_mut_ptr() as *mut RawHeader<Ix>),
+                nodes: slice::from_raw_parts_mut(nodes.as_mut_ptr() as *mut RawNode<T, Ix>, cap),
+            })
+        }
+    }
+
+    /// Create an empty **RawList** in **buf**, overwriting the header.
+    ///
+    /// **buf** must be aligned to *RawList::align()*.
+    pub fn init(buf: &'a mut [u8]) -> Result<Self, RawError>
+    {
+        let list = Self::split(buf)?;
+        *list.header = RawHeader {
+            magic: Ix::MAGIC,
+            node_size: size_of::<RawNode<T, Ix>>() as u32,
+            head: Ix::END,
+            tail: Ix::END,
+            len: Ix::new(0),
+        };
+        Ok(list)
+    }
+
+    /// Attach to the **RawList** that was created in **buf** with *init*,
+    /// maybe by another process.
+    ///
+    /// The header and all links are checked, which takes O(n) time.
+    pub fn attach(buf: &'a mut [u8]) -> Result<Self, RawError>
+    {
+        let list = Self::split(buf)?;
+        list.validate()?;
+        Ok(list)
+    }
+
+    /// Check the header and that the links form one list of *len* nodes.
+    fn validate(&self) -> Result<(), RawError>
+    {
+        validate(self.header, self.nodes)
+    }
+
+    /// Return the number of elements in the RawList.
+    pub fn len(&self) -> usize { self.header.len.index() }
+
+    /// Return **true** if the RawList has no elements.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Return the number of elements the buffer can hold.
+    pub fn capacity(&self) -> usize { self.nodes.len() }
+
+    fn link(&self, term: Terminal) -> Ix
+    {
+        match term {
+            Terminal::Head => self.header.head,
+            Terminal::Tail => self.header.tail,
+        }
+    }
+
+    fn set_link(&mut self, term: Terminal, index: Ix)
+    {
+        match term {
+            Terminal::Head => self.header.head = index,
+            Terminal::Tail => self.header.tail = index,
+        }
+    }
+
+    /// Return an iterator of the elements, in order.
+    pub fn iter(&self) -> RawIter<'_, T, Ix>
+    {
+        RawIter {
+            link: [self.header.head, self.header.tail],
+            nodes: &self.nodes[..self.len()],
+            len: self.len(),
+        }
+    }
+
+    fn push_terminal(&mut self, value: T, term: Terminal) -> Result<(), T>
+    {
+        let index = self.len();
+        if index == self.capacity() {
+            return Err(value);
+        }
+        let t = term.index();
+        let end = self.link(term);
+        let mut link = [Ix::END; 2];
+        link[1 - t] = end;
+        match self.nodes[..index].get_mut(end.index()) {
+            None => self.set_link(term.opposite(), Ix::new(index)), // List was empty
+            Some(n) => n.link[t] = Ix::new(index),
+        }
+        self.set_link(term, Ix::new(index));
+        self.write_node(index, link, value);
+        self.header.len = Ix::new(index + 1);
+        Ok(())
+    }
+
+    /// Insert an element at the beginning of the RawList.
+    ///
+    /// Return the element in **Err** if the buffer is full.
+    pub fn push_front(&mut self, value: T) -> Result<(), T>
+    {
+        self.push_terminal(value, Terminal::Head)
+    }
+
+    /// Insert an element at the end of the RawList.
+    ///
+    /// Return the element in **Err** if the buffer is full.
+    pub fn push_back(&mut self, value: T) -> Result<(), T>
+    {
+        self.push_terminal(value, Terminal::Tail)
+    }
+
+    /// Write the fields of the node at **index**. Assigning a whole
+    /// **RawNode** instead would leave its padding bytes uninitialized.
+    fn write_node(&mut self, index: usize, link: [Ix; 2], value: T)
+    {
+        let node = &mut self.nodes[index];
+        node.link = link;
+        node.value = value;
+    }
+
+    /// Point the neighbors **link** (prev, next) of a node to **to_index**
+    /// instead, or head and tail if there is no such neighbor.
+    fn relink(&mut self, link: [Ix; 2], to_index: [Ix; 2])
+    {
+        let len = self.len();
+        match self.nodes[..len].get_mut(link[0].index()) {
+            None => self.header.head = to_index[1],
+            Some(n) => n.link[1] = to_index[1],
+        }
+        match self.nodes[..len].get_mut(link[1].index()) {
+            None => self.header.tail = to_index[0],
+            Some(n) => n.link[0] = to_index[0],
+        }
+    }
+
+    fn pop_terminal(&mut self, term: Terminal) -> Option<T>
+    {
+        let index = self.link(term).index();
+        let len = self.len();
+        let node = *self.nodes[..len].get(index)?;
+        self.relink(node.link, node.link);
+        let last = len - 1;
+        if index != last {
+            let moved = self.nodes[last];
+            self.relink(moved.link, [Ix::new(index); 2]);
+            self.write_node(index, moved.link, moved.value);
+        }
+        self.header.len = Ix::new(last);
+        Some(node.value)
+    }
+
+    /// Remove the element at the beginning of the RawList and return it,
+    /// or return **None** if the RawList is empty.
+    pub fn pop_front(&mut self) -> Option<T>
+    {
+        self.pop_terminal(Terminal::Head)
+    }
+
+    /// Remove the element at the end of the RawList and return it,
+    /// or return **None** if the RawList is empty.
+    pub fn pop_back(&mut self) -> Option<T>
+    {
+        self.pop_terminal(Terminal::Tail)
+    }
+
+    /// Return the head and tail links and the nodes in use.
+    #[cfg(feature = "std")]
+    pub(crate) fn parts(&self) -> ([Ix; 2], &[RawNode<T, Ix>])
+    {
+        ([self.header.head, self.header.tail], &self.nodes[..self.len()])
+    }
+
+    /// Overwrite the list with **nodes** (links, value) and the head and
+    /// tail **link**, without checking them.
+    ///
+    /// Only the fields are written, so padding bytes keep their contents.
+    pub(crate) fn set_parts<I>(&mut self, link: [Ix; 2], nodes: I)
+        where I: IntoIterator<Item=([Ix; 2], T)>
+    {
+        let mut len = 0;
+        for (node, (link, value)) in self.nodes.iter_mut().zip(nodes) {
+            node.link = link;
+            node.value = value;
+            len += 1;
+        }
+        self.header.head = link[0];
+        self.header.tail = link[1];
+        self.header.len = Ix::new(len);
+    }
+
+    /// Remove all elements.
+    pub fn clear(&mut self)
+    {
+        self.header.head = Ix::END;
+        self.header.tail = Ix::END;
+        self.header.len = Ix::new(0);
+    }
+}
+
+/// Check **header** and that the links of **nodes** form one list of *len*
+/// nodes.
+fn validate<T, Ix: RawIndex>(header: &RawHeader<Ix>, nodes: &[RawNode<T, Ix>])
+    -> Result<(), RawError>
+{
+    if header.magic != Ix::MAGIC {
+        return Err(RawError::BadMagic);
+    }
+    if header.node_size as usize != size_of::<RawNode<T, Ix>>() {
+        return Err(RawError::NodeSize);
+    }
+    let len = header.len.index();
+    if len > nodes.len() {
+        return Err(if header.len == Ix::END { RawError::Corrupt } else { RawError::TooSmall });
+    }
+    // Walk from the head; every prev link must point back to the node
+    // before, which also rules out cycles.
+    let mut prev = Ix::END;
+    let mut index = header.head;
+    for _ in 0..len {
+        match nodes[..len].get(index.index()) {
+            Some(n) if n.link[0] == prev => {
+                prev = index;
+                index = n.link[1];
+            }
+            _ => return Err(RawError::Corrupt),
+        }
+    }
+    if index != Ix::END || prev != header.tail {
+        return Err(RawError::Corrupt);
+    }
+    Ok(())
+}
+
+impl<'a, T: Plain + fmt::Debug, Ix: RawIndex> fmt::Debug for RawList<'a, T, Ix>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: Plain + 'a, Ix: RawIndex + 'a> ArchivedList<'a, T, Ix>
+{
+    /// Return the alignment the buffer needs.
+    pub fn align() -> usize { RawList::<T, Ix>::align() }
+
+    /// Return the size in bytes of the archive of a list of **len**
+    /// elements.
+    ///
+    /// **Panics** if the size overflows `usize`.
+    pub fn size_for(len: usize) -> usize { RawList::<T, Ix>::size_for(len) }
+
+    /// Write **list** to **buf**, which must be aligned to
+    /// *ArchivedList::align()*, and return the number of bytes written,
+    /// which is *ArchivedList::size_for(list.len())*.
+    ///
+    /// The nodes are written in the order of the list's node array, so the
+    /// elements are linked just like in **list**.
+    pub fn write<S, L>(list: &List<T, S, L>, buf: &mut [u8]) -> Result<usize, RawError>
+        where S: Storage<Node<T>>,
+              L: Storage<Slot>,
+    {
+        fn link<Ix: RawIndex>(index: usize) -> Ix
+        {
+            if index == END { Ix::END } else { Ix::new(index) }
+        }
+
+        if list.len() > RawList::<T, Ix>::check_buffer(buf)? {
+            return Err(RawError::TooSmall);
+        }
+        let mut raw = RawList::<T, Ix>::init(buf)?;
+        let nodes = list.nodes.iter().map(|n| ([link(n.prev()), link(n.next())], n.value));
+        raw.set_parts([link(list.head()), link(list.tail())], nodes);
+        Ok(Self::size_for(list.len()))
+    }
+
+    /// Create a view of the list in **buf**, which must be aligned to
+    /// *ArchivedList::align()*.
+    ///
+    /// The header and all links are checked, which takes O(n) time.
+    pub fn from_bytes(buf: &'a [u8]) -> Result<Self, RawError>
+    {
+        let cap = RawList::<T, Ix>::check_buffer(buf)?;
+        let (header, nodes) = buf.split_at(RawList::<T, Ix>::nodes_offset());
+        // The buffer is aligned, large enough, and any bytes are valid
+        // values of the header and the nodes.
+        let list = unsafe {
+            ArchivedList {
+                header: &*(header.as_ptr() as *const RawHeader<Ix>),
+                nodes: slice::from_raw_parts(nodes.as_ptr() as *const RawNode<T, Ix>, cap),
+            }
+        };
+        validate(list.header, list.nodes)?;
+        Ok(list)
+    }
+
+    /// Return the number of elements in the list.
+    pub fn len(&self) -> usize { self.header.len.index() }
+
+    /// Return **true** if the list has no elements.
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Return an iterator of the elements, in order.
+    pub fn iter(&self) -> RawIter<'a, T, Ix>
+    {
+        RawIter {
+            link: [self.header.head, self.header.tail],
+            nodes: &self.nodes[..self.len()],
+            len: self.len(),
+        }
+    }
+}
+
+impl<'a, T, Ix> Copy for ArchivedList<'a, T, Ix> { }
+
+impl<'a, T, Ix> Clone for ArchivedList<'a, T, Ix>
+{
+    fn clone(&self) -> Self { *self }
+}
+
+impl<'a, T: Plain + fmt::Debug, Ix: RawIndex> fmt::Debug for ArchivedList<'a, T, Ix>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: Plain, Ix: RawIndex> IntoIterator for ArchivedList<'a, T, Ix>
+{
+    type Item = &'a T;
+    type IntoIter = RawIter<'a, T, Ix>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+impl<'a, T: Plain, Ix: RawIndex> IntoIterator for &ArchivedList<'a, T, Ix>
+{
+    type Item = &'a T;
+    type IntoIter = RawIter<'a, T, Ix>;
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+impl<'a, T: Plain, Ix: RawIndex> RawIter<'a, T, Ix>
+{
+    /// Step the iterator from the head or tail
+    fn next_terminal(&mut self, term: Terminal) -> Option<&'a T>
+    {
+        if self.len == 0 {
+            return None;
+        }
+        let h = term.index();
+        let t = term.opposite().index();
+        let n = &self.nodes[self.link[h].index()];
+        self.len -= 1;
+        self.link[h] = n.link[t];
+        Some(&n.value)
+    }
+}
+
+impl<'a, T: 'a, Ix: RawIndex> Clone for RawIter<'a, T, Ix>
+{
+    fn clone(&self) -> Self { RawIter { ..*self } }
+}
+
+impl<'a, T: Plain, Ix: RawIndex> Iterator for RawIter<'a, T, Ix>
+{
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> { self.next_terminal(Terminal::Head) }
+
+    fn size_hint(&self) -> (usize, Option<usize>) { (self.len, Some(self.len)) }
+}
+
+impl<'a, T: Plain, Ix: RawIndex> DoubleEndedIterator for RawIter<'a, T, Ix>
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> { self.next_terminal(Terminal::Tail) }
+}
+
+impl<'a, T: Plain, Ix: RawIndex> ExactSizeIterator for RawIter<'a, T, Ix> { }
+
+impl<'a, T: Plain, Ix: RawIndex> FusedIterator for RawIter<'a, T, Ix> { }
@@ -0,0 +1,327 @@
+//! Serialization with serde, with the `serde` crate feature.
+//!
+//! A **List** serializes as a sequence of its elements, in order. The
+//! **ListLayout** wrapper instead serializes the node array with its links,
+//! and the slot table, so that handles are still valid after a round trip:
+//!
+//! ```ignore
+//! struct ListLayout {
+//!     /// Head, Tail
+//!     link: [Option<usize>; 2],
+//!     /// Prev, Next, Slot, Value
+//!     nodes: Vec<(Option<usize>, Option<usize>, usize, T)>,
+//!     /// Node or next free slot, Generation
+//!     slots: Vec<(Option<usize>, usize)>,
+//!     /// First free slot
+//!     free: Option<usize>,
+//! }
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+use alloc::vec::Vec;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use {List, Node, Slot, Storage, END};
+
+impl<T: Serialize, S: Storage<Node<T>>, L: Storage<Slot>> Serialize for List<T, S, L>
+{
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, T, S, L> Deserialize<'de> for List<T, S, L>
+    where T: Deserialize<'de>,
+          S: Storage<Node<T>> + Default,
+          L: Storage<Slot> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        deserializer.deserialize_seq(ListVisitor(PhantomData))
+    }
+}
+
+struct ListVisitor<T, S, L>(PhantomData<(T, S, L)>);
+
+impl<'de, T, S, L> Visitor<'de> for ListVisitor<T, S, L>
+    where T: Deserialize<'de>,
+          S: Storage<Node<T>> + Default,
+          L: Storage<Slot> + Default,
+{
+    type Value = List<T, S, L>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    {
+        let mut list = List::default();
+        while let Some(value) = seq.next_element()? {
+            list.try_push_back(value).map_err(|e| de::Error::custom(e.error()))?;
+        }
+        Ok(list)
+    }
+}
+
+/// **ListLayout** is a wrapper of a **List**, or a reference to one, that
+/// serializes the list's node array with its links, and its slot table, so
+/// that handles are still valid after a round trip.
+///
+/// Deserializing a **ListLayout** checks that the links form one list and
+/// that the slot table is consistent, and rejects the input otherwise.
+#[derive(Copy, Clone, Debug)]
+pub struct ListLayout<L>(pub L);
+
+impl<T, S: Storage<Node<T>>, L: Storage<Slot>> List<T, S, L>
+{
+    /// Return a wrapper that serializes the List in the layout preserving
+    /// form; see **ListLayout**.
+    pub fn serialize_layout(&self) -> ListLayout<&Self>
+    {
+        ListLayout(self)
+    }
+}
+
+fn link(index: usize) -> Option<usize>
+{
+    if index == END { None } else { Some(index) }
+}
+
+fn unlink(index: Option<usize>) -> usize
+{
+    index.unwrap_or(END)
+}
+
+/// Serialize the elements of **iter** as a sequence.
+struct SerializeIter<I>(I);
+
+impl<I> Serialize for SerializeIter<I>
+    where I: Clone + Iterator,
+          I::Item: Serialize,
+{
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    {
+        serializer.collect_seq(self.0.clone())
+    }
+}
+
+fn serialize_layout<T, S, L, Z>(list: &List<T, S, L>, serializer: Z) -> Result<Z::Ok, Z::Error>
+    where T: Serialize,
+          S: Storage<Node<T>>,
+          L: Storage<Slot>,
+          Z: Serializer,
+{
+    let nodes = list.nodes.iter()
+        .map(|n| (link(n.prev()), link(n.next()), n.slot, &n.value));
+    let slots = list.slots.iter().map(|s| (link(s.node), s.gen));
+    let mut st = serializer.serialize_struct("ListLayout", 4)?;
+    st.serialize_field("link", &[link(list.head()), link(list.tail())])?;
+    st.serialize_field("nodes", &SerializeIter(nodes))?;
+    st.serialize_field("slots", &SerializeIter(slots))?;
+    st.serialize_field("free", &link(list.free))?;
+    st.end()
+}
+
+impl<T: Serialize, S: Storage<Node<T>>, L: Storage<Slot>> Serialize for ListLayout<&List<T, S, L>>
+{
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    {
+        serialize_layout(self.0, serializer)
+    }
+}
+
+impl<T: Serialize, S: Storage<Node<T>>, L: Storage<Slot>> Serialize for ListLayout<List<T, S, L>>
+{
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error>
+    {
+        serialize_layout(&self.0, serializer)
+    }
+}
+
+const FIELDS: &[&str] = &["link", "nodes", "slots", "free"];
+
+enum Field { Link, Nodes, Slots, Free }
+
+impl<'de> Deserialize<'de> for Field
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        struct FieldVisitor;
+
+        impl<'de> Visitor<'de> for FieldVisitor
+        {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+            {
+                f.write_str("a field name")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E>
+            {
+                match value {
+                    "link" => Ok(Field::Link),
+                    "nodes" => Ok(Field::Nodes),
+                    "slots" => Ok(Field::Slots),
+                    "free" => Ok(Field::Free),
+                    _ => Err(de::Error::unknown_field(value, FIELDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+type NodeParts<T> = Vec<(Option<usize>, Option<usize>, usize, T)>;
+type SlotParts = Vec<(Option<usize>, usize)>;
+
+impl<'de, T, S, L> Deserialize<'de> for ListLayout<List<T, S, L>>
+    where T: Deserialize<'de>,
+          S: Storage<Node<T>> + Default,
+          L: Storage<Slot> + Default,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        deserializer.deserialize_struct("ListLayout", FIELDS, LayoutVisitor(PhantomData))
+    }
+}
+
+struct LayoutVisitor<T, S, L>(PhantomData<(T, S, L)>);
+
+impl<'de, T, S, L> Visitor<'de> for LayoutVisitor<T, S, L>
+    where T: Deserialize<'de>,
+          S: Storage<Node<T>> + Default,
+          L: Storage<Slot> + Default,
+{
+    type Value = ListLayout<List<T, S, L>>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str("struct ListLayout")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    {
+        macro_rules! element {
+            ($i:expr) => {
+                seq.next_element()?.ok_or_else(|| de::Error::invalid_length($i, &self))?
+            }
+        }
+        let link = element!(0);
+        let nodes = element!(1);
+        let slots = element!(2);
+        let free = element!(3);
+        from_parts(link, nodes, slots, free)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error>
+    {
+        let mut link = None;
+        let mut nodes = None;
+        let mut slots = None;
+        let mut free = None;
+        while let Some(key) = map.next_key()? {
+            macro_rules! field {
+                ($x:ident, $name:expr) => {{
+                    if $x.is_some() {
+                        return Err(de::Error::duplicate_field($name));
+                    }
+                    $x = Some(map.next_value()?);
+                }}
+            }
+            match key {
+                Field::Link => field!(link, "link"),
+                Field::Nodes => field!(nodes, "nodes"),
+                Field::Slots => field!(slots, "slots"),
+                Field::Free => field!(free, "free"),
+            }
+        }
+        from_parts(link.ok_or_else(|| de::Error::missing_field("link"))?,
+                   nodes.ok_or_else(|| de::Error::missing_field("nodes"))?,
+                   slots.ok_or_else(|| de::Error::missing_field("slots"))?,
+                   free.ok_or_else(|| de::Error::missing_field("free"))?)
+    }
+}
+
+/// Build a List from its deserialized parts, and check it.
+fn from_parts<T, S, L, E>(link: [Option<usize>; 2], nodes: NodeParts<T>, slots: SlotParts,
+                          free: Option<usize>)
+    -> Result<ListLayout<List<T, S, L>>, E>
+    where S: Storage<Node<T>> + Default,
+          L: Storage<Slot> + Default,
+          E: de::Error,
+{
+    let mut list = List::<T, S, L>::default();
+    list.nodes.try_reserve(nodes.len()).map_err(E::custom)?;
+    for (prev, next, slot, value) in nodes {
+        let node = Node { link: [unlink(prev), unlink(next)], slot, value };
+        if list.nodes.push(node).is_err() {
+            return Err(E::custom("ListLayout: the storage is full"));
+        }
+    }
+    list.slots.try_reserve(slots.len()).map_err(E::custom)?;
+    for (node, gen) in slots {
+        if list.slots.push(Slot { node: unlink(node), gen }).is_err() {
+            return Err(E::custom("ListLayout: the slot storage is full"));
+        }
+    }
+    list.link = [unlink(link[0]), unlink(link[1])];
+    list.free = unlink(free);
+    if !is_consistent(&list) {
+        return Err(E::custom("ListLayout: the links or slots are not consistent"));
+    }
+    Ok(ListLayout(list))
+}
+
+/// Return **true** if the links form one list of all the nodes, every node
+/// owns its slot, and the free list has every other slot.
+fn is_consistent<T, S: Storage<Node<T>>, L: Storage<Slot>>(list: &List<T, S, L>) -> bool
+{
+    let nodes = &list.nodes[..];
+    let slots = &list.slots[..];
+
+    // Walk from the head; every prev link must point back to the node
+    // before, which also rules out cycles.
+    let mut prev = END;
+    let mut index = list.head();
+    for _ in 0..nodes.len() {
+        match nodes.get(index) {
+            Some(n) if n.prev() == prev => {
+                prev = index;
+                index = n.next();
+            }
+            _ => return false,
+        }
+    }
+    if index != END || prev != list.tail() {
+        return false;
+    }
+
+    let owned = |slot: usize| {
+        nodes.get(slots[slot].node).is_some_and(|n| n.slot == slot)
+    };
+    // Different nodes can not own the same slot, since it points to one.
+    let all_owned = nodes.iter().enumerate().all(|(index, n)| {
+        slots.get(n.slot).is_some_and(|s| s.node == index)
+    });
+    if !all_owned {
+        return false;
+    }
+    let free_len = slots.len() - nodes.len();
+    let mut count = 0;
+    let mut free = list.free;
+    while free != END {
+        if count == free_len || free >= slots.len() || owned(free) {
+            return false;
+        }
+        count += 1;
+        free = slots[free].node;
+    }
+    count == free_len
+}
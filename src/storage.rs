@@ -0,0 +1,706 @@
+//! Backing storage for the nodes of a **List**.
+//!
+//! A **List** keeps its nodes in a growable array, a `Vec` by default.
+//! The **Storage** trait lets the list use any other array of nodes:
+//! this module has **InlineStorage**, which starts out inline and moves to
+//! the heap when it grows too large, **SliceStorage**, which uses a
+//! buffer provided by the caller, and **AllocStorage**, which allocates
+//! with an **Alloc**, for example an arena.
+//!
+//! With the `allocator_api` crate feature (requires nightly Rust), the
+//! storage can also be a `Vec<Node<T>, A>` with any
+//! `core::alloc::Allocator` **A**.
+//!
+//! The list's slot table, which backs its handles, is a second storage,
+//! of **Slot**; it is a `Vec` unless the list is created with
+//! *List::with_storages*, *List::with_alloc* or *List::new_in*.
+
+use core::alloc::Layout;
+use core::cmp;
+use core::fmt;
+use core::marker::PhantomData;
+use core::mem::{self, MaybeUninit};
+use core::ops::{Deref, DerefMut};
+use core::ptr::{self, NonNull};
+use core::slice;
+use alloc::alloc as global;
+use alloc::collections;
+use alloc::vec::Vec;
+#[cfg(feature = "allocator_api")]
+use core::alloc::Allocator;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+/// The error type for fallible reservation of room in a **Storage**.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TryReserveError
+{
+    kind: ErrorKind,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum ErrorKind
+{
+    /// The storage can not grow.
+    Full,
+    /// The capacity would overflow.
+    CapacityOverflow,
+    /// The allocator of an **AllocStorage** failed.
+    AllocError,
+    /// The allocator failed, or the capacity overflowed.
+    Alloc(collections::TryReserveError),
+}
+
+impl TryReserveError
+{
+    /// Create an error for a storage that is full and can not grow.
+    pub fn full() -> Self
+    {
+        TryReserveError { kind: ErrorKind::Full }
+    }
+}
+
+impl From<collections::TryReserveError> for TryReserveError
+{
+    fn from(error: collections::TryReserveError) -> Self
+    {
+        TryReserveError { kind: ErrorKind::Alloc(error) }
+    }
+}
+
+impl fmt::Display for TryReserveError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        match self.kind {
+            ErrorKind::Full => f.write_str("the storage is full"),
+            ErrorKind::CapacityOverflow => f.write_str("capacity overflow"),
+            ErrorKind::AllocError => f.write_str("memory allocation failed"),
+            ErrorKind::Alloc(ref error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for TryReserveError
+{
+    fn source(&self) -> Option<&(dyn Error + 'static)>
+    {
+        match self.kind {
+            ErrorKind::Alloc(ref error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// A growable array of **E**, used as the backing storage of a **List**.
+///
+/// The storage derefs to the slice of the elements it holds; the list
+/// reads and links its nodes through that slice, and uses the methods of
+/// this trait to add and remove them at the end.
+pub trait Storage<E> : Deref<Target = [E]> + DerefMut
+{
+    /// Return the number of elements the storage can hold without
+    /// reallocating.
+    fn capacity(&self) -> usize;
+
+    /// Reserve room for at least **additional** more elements, if the
+    /// storage can grow.
+    fn reserve(&mut self, additional: usize);
+
+    /// Reserve room for at least **additional** more elements, or return
+    /// an error if that is not possible. The storage is unchanged on error.
+    ///
+    /// The default implementation only succeeds if there is room already.
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>
+    {
+        if self.capacity() - self.len() >= additional {
+            Ok(())
+        } else {
+            Err(TryReserveError::full())
+        }
+    }
+
+    /// Append an element, or return it in **Err** if there is no room.
+    fn push(&mut self, elt: E) -> Result<(), E>;
+
+    /// Remove the last element and return it, or return **None** if the
+    /// storage is empty.
+    fn pop(&mut self) -> Option<E>;
+
+    /// Remove the element at **index** and return it, moving the last
+    /// element into its place.
+    ///
+    /// **Panics** if **index** is out of bounds.
+    fn swap_remove(&mut self, index: usize) -> E
+    {
+        let last = self.len() - 1;
+        self.swap(index, last);
+        self.pop().unwrap()
+    }
+
+    /// Remove the elements from **len** onwards.
+    fn truncate(&mut self, len: usize)
+    {
+        while self.len() > len {
+            self.pop();
+        }
+    }
+}
+
+macro_rules! vec_storage_methods {
+    () => {
+        fn capacity(&self) -> usize { Vec::capacity(self) }
+
+        fn reserve(&mut self, additional: usize) { Vec::reserve(self, additional) }
+
+        fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>
+        {
+            Ok(Vec::try_reserve(self, additional)?)
+        }
+
+        #[inline]
+        fn push(&mut self, elt: E) -> Result<(), E>
+        {
+            Vec::push(self, elt);
+            Ok(())
+        }
+
+        fn pop(&mut self) -> Option<E> { Vec::pop(self) }
+
+        fn swap_remove(&mut self, index: usize) -> E { Vec::swap_remove(self, index) }
+
+        fn truncate(&mut self, len: usize) { Vec::truncate(self, len) }
+    }
+}
+
+#[cfg(not(feature = "allocator_api"))]
+impl<E> Storage<E> for Vec<E>
+{
+    vec_storage_methods!();
+}
+
+#[cfg(feature = "allocator_api")]
+impl<E, A: Allocator> Storage<E> for Vec<E, A>
+{
+    vec_storage_methods!();
+}
+
+/// Storage in a buffer provided by the caller. It never allocates, and
+/// pushing to it fails when the buffer is full.
+///
+/// For a **List** that does not allocate at all, use one for the nodes and
+/// one for the slot table, with *List::with_storages*.
+///
+/// The elements left in the storage are dropped when it is dropped.
+pub struct SliceStorage<'a, E: 'a>
+{
+    buf: &'a mut [MaybeUninit<E>],
+    len: usize,
+}
+
+impl<'a, E: 'a> SliceStorage<'a, E>
+{
+    /// Create a new, empty **SliceStorage** using **buf**.
+    pub fn new(buf: &'a mut [MaybeUninit<E>]) -> Self
+    {
+        SliceStorage { buf, len: 0 }
+    }
+}
+
+impl<'a, E: 'a> Deref for SliceStorage<'a, E>
+{
+    type Target = [E];
+    fn deref(&self) -> &[E]
+    {
+        // Elements below len are initialized.
+        unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const E, self.len) }
+    }
+}
+
+impl<'a, E: 'a> DerefMut for SliceStorage<'a, E>
+{
+    fn deref_mut(&mut self) -> &mut [E]
+    {
+        // Elements below len are initialized.
+        unsafe { slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut E, self.len) }
+    }
+}
+
+impl<'a, E: 'a> Storage<E> for SliceStorage<'a, E>
+{
+    fn capacity(&self) -> usize { self.buf.len() }
+
+    fn reserve(&mut self, _additional: usize) { }
+
+    fn push(&mut self, elt: E) -> Result<(), E>
+    {
+        match self.buf.get_mut(self.len) {
+            None => Err(elt),
+            Some(spot) => {
+                *spot = MaybeUninit::new(elt);
+                self.len += 1;
+                Ok(())
+            }
+        }
+    }
+
+    fn pop(&mut self) -> Option<E>
+    {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // The element is past the new length, so it is read only once.
+        Some(unsafe { ptr::read(self.buf[self.len].as_ptr()) })
+    }
+}
+
+impl<'a, E: 'a> Drop for SliceStorage<'a, E>
+{
+    fn drop(&mut self)
+    {
+        let len = mem::replace(&mut self.len, 0);
+        for elt in &mut self.buf[..len] {
+            unsafe {
+                ptr::drop_in_place(elt.as_mut_ptr());
+            }
+        }
+    }
+}
+
+impl<'a, E: fmt::Debug + 'a> fmt::Debug for SliceStorage<'a, E>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Storage that holds up to **N** elements inline, and moves them to a
+/// `Vec` when it grows larger than that.
+pub struct InlineStorage<E, const N: usize>
+{
+    repr: Repr<E, N>,
+}
+
+enum Repr<E, const N: usize>
+{
+    Inline {
+        buf: [MaybeUninit<E>; N],
+        len: usize,
+    },
+    Heap(Vec<E>),
+}
+
+impl<E, const N: usize> InlineStorage<E, N>
+{
+    /// Create a new, empty **InlineStorage**.
+    pub fn new() -> Self
+    {
+        InlineStorage {
+            repr: Repr::Inline {
+                // An array of uninitialized values needs no initialization.
+                buf: unsafe { MaybeUninit::uninit().assume_init() },
+                len: 0,
+            },
+        }
+    }
+
+    /// Return **true** if the elements have moved to the heap.
+    pub fn spilled(&self) -> bool
+    {
+        match self.repr {
+            Repr::Inline { .. } => false,
+            Repr::Heap(_) => true,
+        }
+    }
+
+    /// Move the elements to a `Vec` with room for **additional** more.
+    fn spill(&mut self, additional: usize)
+    {
+        if let Err(error) = self.try_spill(additional) {
+            panic!("InlineStorage: {}", error);
+        }
+    }
+
+    /// Move the elements to a `Vec` with room for **additional** more, or
+    /// return an error if the allocation fails.
+    fn try_spill(&mut self, additional: usize) -> Result<(), TryReserveError>
+    {
+        if let Repr::Inline { ref mut buf, ref mut len } = self.repr {
+            let needed = len.checked_add(additional)
+                .ok_or(TryReserveError { kind: ErrorKind::CapacityOverflow })?;
+            let mut vec = Vec::new();
+            vec.try_reserve(needed)?;
+            let n = mem::replace(len, 0);
+            for elt in &buf[..n] {
+                // Each element is read once, and len is already reset.
+                vec.push(unsafe { ptr::read(elt.as_ptr()) });
+            }
+            self.repr = Repr::Heap(vec);
+        }
+        Ok(())
+    }
+}
+
+impl<E, const N: usize> Default for InlineStorage<E, N>
+{
+    fn default() -> Self { InlineStorage::new() }
+}
+
+impl<E, const N: usize> Deref for InlineStorage<E, N>
+{
+    type Target = [E];
+    fn deref(&self) -> &[E]
+    {
+        match self.repr {
+            // Elements below len are initialized.
+            Repr::Inline { ref buf, len } => unsafe {
+                slice::from_raw_parts(buf.as_ptr() as *const E, len)
+            },
+            Repr::Heap(ref vec) => vec,
+        }
+    }
+}
+
+impl<E, const N: usize> DerefMut for InlineStorage<E, N>
+{
+    fn deref_mut(&mut self) -> &mut [E]
+    {
+        match self.repr {
+            // Elements below len are initialized.
+            Repr::Inline { ref mut buf, len } => unsafe {
+                slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut E, len)
+            },
+            Repr::Heap(ref mut vec) => vec,
+        }
+    }
+}
+
+impl<E, const N: usize> Storage<E> for InlineStorage<E, N>
+{
+    fn capacity(&self) -> usize
+    {
+        match self.repr {
+            Repr::Inline { .. } => N,
+            Repr::Heap(ref vec) => vec.capacity(),
+        }
+    }
+
+    fn reserve(&mut self, additional: usize)
+    {
+        match self.repr {
+            Repr::Inline { len, .. } => {
+                if additional > N - len {
+                    self.spill(additional);
+                }
+            }
+            Repr::Heap(ref mut vec) => vec.reserve(additional),
+        }
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>
+    {
+        match self.repr {
+            Repr::Inline { len, .. } => {
+                if additional > N - len {
+                    self.try_spill(additional)?;
+                }
+                Ok(())
+            }
+            Repr::Heap(ref mut vec) => Ok(vec.try_reserve(additional)?),
+        }
+    }
+
+    fn push(&mut self, elt: E) -> Result<(), E>
+    {
+        if let Repr::Inline { ref mut buf, ref mut len } = self.repr {
+            if *len < N {
+                buf[*len] = MaybeUninit::new(elt);
+                *len += 1;
+                return Ok(());
+            }
+        }
+        self.spill(N.max(1));
+        match self.repr {
+            Repr::Heap(ref mut vec) => vec.push(elt),
+            Repr::Inline { .. } => unreachable!(),
+        }
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<E>
+    {
+        match self.repr {
+            Repr::Inline { ref buf, ref mut len } => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                // The element is past the new length, so it is read only once.
+                Some(unsafe { ptr::read(buf[*len].as_ptr()) })
+            }
+            Repr::Heap(ref mut vec) => vec.pop(),
+        }
+    }
+}
+
+impl<E, const N: usize> Drop for InlineStorage<E, N>
+{
+    fn drop(&mut self)
+    {
+        if let Repr::Inline { ref mut buf, ref mut len } = self.repr {
+            let n = mem::replace(len, 0);
+            for elt in &mut buf[..n] {
+                unsafe {
+                    ptr::drop_in_place(elt.as_mut_ptr());
+                }
+            }
+        }
+    }
+}
+
+impl<E: Clone, const N: usize> Clone for InlineStorage<E, N>
+{
+    fn clone(&self) -> Self
+    {
+        let mut storage = InlineStorage::new();
+        storage.reserve(self.len());
+        for elt in self.iter() {
+            let _ = storage.push(elt.clone());
+        }
+        storage
+    }
+}
+
+impl<E: fmt::Debug, const N: usize> fmt::Debug for InlineStorage<E, N>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// The error returned by an **Alloc** that could not allocate memory.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct AllocError;
+
+impl fmt::Display for AllocError
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.write_str("memory allocation failed")
+    }
+}
+
+/// A minimal allocator interface for **AllocStorage**, that works on
+/// stable Rust.
+///
+/// # Safety
+///
+/// A block returned by *allocate* must be valid for **layout** and must not
+/// overlap any other live block, until it is passed to *deallocate*.
+pub unsafe trait Alloc
+{
+    /// Allocate a block of memory for **layout**, which has a nonzero size.
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>;
+
+    /// Deallocate a block of memory.
+    ///
+    /// # Safety
+    ///
+    /// **ptr** must have been returned by *allocate* on this allocator with
+    /// the same **layout**, and not deallocated already.
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout);
+}
+
+/// The global memory allocator.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Global;
+
+unsafe impl Alloc for Global
+{
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>
+    {
+        NonNull::new(unsafe { global::alloc(layout) }).ok_or(AllocError)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout)
+    {
+        global::dealloc(ptr.as_ptr(), layout)
+    }
+}
+
+unsafe impl<A: Alloc + ?Sized> Alloc for &A
+{
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>
+    {
+        (**self).allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout)
+    {
+        (**self).deallocate(ptr, layout)
+    }
+}
+
+/// A growable array that allocates its memory with **A**.
+pub struct AllocStorage<E, A: Alloc = Global>
+{
+    ptr: NonNull<E>,
+    cap: usize,
+    len: usize,
+    alloc: A,
+    marker: PhantomData<E>,
+}
+
+unsafe impl<E: Send, A: Alloc + Send> Send for AllocStorage<E, A> { }
+unsafe impl<E: Sync, A: Alloc + Sync> Sync for AllocStorage<E, A> { }
+
+impl<E, A: Alloc> AllocStorage<E, A>
+{
+    /// Create a new, empty **AllocStorage** that allocates with **alloc**.
+    ///
+    /// It does not allocate until elements are pushed.
+    pub fn new_in(alloc: A) -> Self
+    {
+        AllocStorage {
+            ptr: NonNull::dangling(),
+            cap: if mem::size_of::<E>() == 0 { usize::MAX } else { 0 },
+            len: 0,
+            alloc,
+            marker: PhantomData,
+        }
+    }
+
+    /// Return a reference to the allocator.
+    pub fn allocator(&self) -> &A { &self.alloc }
+
+    /// Move the elements to a new block with room for **cap** elements.
+    fn grow_to(&mut self, cap: usize) -> Result<(), TryReserveError>
+    {
+        let layout = Layout::array::<E>(cap)
+            .map_err(|_| TryReserveError { kind: ErrorKind::CapacityOverflow })?;
+        let ptr = self.alloc.allocate(layout)
+            .map_err(|_| TryReserveError { kind: ErrorKind::AllocError })?
+            .cast::<E>();
+        unsafe {
+            ptr::copy_nonoverlapping(self.ptr.as_ptr(), ptr.as_ptr(), self.len);
+            self.free();
+        }
+        self.ptr = ptr;
+        self.cap = cap;
+        Ok(())
+    }
+
+    /// Deallocate the block, without dropping the elements.
+    unsafe fn free(&mut self)
+    {
+        if self.cap != 0 && mem::size_of::<E>() != 0 {
+            let layout = Layout::array::<E>(self.cap).unwrap();
+            self.alloc.deallocate(self.ptr.cast(), layout);
+        }
+    }
+}
+
+impl<E, A: Alloc + Default> Default for AllocStorage<E, A>
+{
+    fn default() -> Self { AllocStorage::new_in(A::default()) }
+}
+
+impl<E, A: Alloc> Deref for AllocStorage<E, A>
+{
+    type Target = [E];
+    fn deref(&self) -> &[E]
+    {
+        // Elements below len are initialized.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<E, A: Alloc> DerefMut for AllocStorage<E, A>
+{
+    fn deref_mut(&mut self) -> &mut [E]
+    {
+        // Elements below len are initialized.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<E, A: Alloc> Storage<E> for AllocStorage<E, A>
+{
+    fn capacity(&self) -> usize { self.cap }
+
+    fn reserve(&mut self, additional: usize)
+    {
+        if let Err(error) = self.try_reserve(additional) {
+            panic!("AllocStorage: {}", error);
+        }
+    }
+
+    fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError>
+    {
+        if self.cap - self.len >= additional {
+            return Ok(());
+        }
+        let needed = self.len.checked_add(additional)
+            .ok_or(TryReserveError { kind: ErrorKind::CapacityOverflow })?;
+        self.grow_to(cmp::max(needed, cmp::max(self.cap.saturating_mul(2), 4)))
+    }
+
+    fn push(&mut self, elt: E) -> Result<(), E>
+    {
+        if self.len == self.cap && self.try_reserve(1).is_err() {
+            return Err(elt);
+        }
+        unsafe {
+            ptr::write(self.ptr.as_ptr().add(self.len), elt);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    fn pop(&mut self) -> Option<E>
+    {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        // The element is past the new length, so it is read only once.
+        Some(unsafe { ptr::read(self.ptr.as_ptr().add(self.len)) })
+    }
+}
+
+impl<E, A: Alloc> Drop for AllocStorage<E, A>
+{
+    fn drop(&mut self)
+    {
+        let len = mem::replace(&mut self.len, 0);
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), len));
+            self.free();
+        }
+    }
+}
+
+impl<E: Clone, A: Alloc + Clone> Clone for AllocStorage<E, A>
+{
+    fn clone(&self) -> Self
+    {
+        let mut storage = AllocStorage::new_in(self.alloc.clone());
+        storage.reserve(self.len);
+        for elt in self.iter() {
+            let _ = storage.push(elt.clone());
+        }
+        storage
+    }
+}
+
+impl<E: fmt::Debug, A: Alloc> fmt::Debug for AllocStorage<E, A>
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
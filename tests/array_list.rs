@@ -0,0 +1,174 @@
+extern crate itertools as it;
+extern crate ixlist;
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::mem::size_of;
+
+use ixlist::ArrayList;
+
+#[test]
+fn push_pop()
+{
+    let mut l = ArrayList::<_, 4>::new();
+    assert_eq!(l.pop_front(), None);
+    assert_eq!(l.try_push_back(1), Ok(()));
+    assert_eq!(l.try_push_front(0), Ok(()));
+    assert_eq!(l.try_push_back(2), Ok(()));
+    assert_eq!(l.try_push_front(-1), Ok(()));
+    assert!(l.is_full());
+    assert_eq!(l.try_push_back(3), Err(3));
+    assert_eq!(l.try_push_front(3), Err(3));
+    it::assert_equal(l.iter(), &[-1, 0, 1, 2]);
+    it::assert_equal(l.iter().rev(), &[2, 1, 0, -1]);
+    assert_eq!(format!("{:?}", l), "[-1, 0, 1, 2]");
+
+    assert_eq!(l.pop_front(), Some(-1));
+    assert_eq!(l.pop_back(), Some(2));
+    for elt in &mut l {
+        *elt *= 10;
+    }
+    let copy = l.clone();
+    it::assert_equal(l.iter_mut().rev(), &mut [10, 0]);
+    assert_eq!(l.pop_back(), Some(10));
+    assert_eq!(l.pop_back(), Some(0));
+    assert_eq!(l.pop_back(), None);
+    assert!(l.is_empty());
+    it::assert_equal(&copy, &[0, 10]);
+}
+
+#[test]
+fn index_type()
+{
+    assert!(size_of::<ArrayList<u8, 16, u8>>() < size_of::<ArrayList<u8, 16, usize>>());
+    let mut l = ArrayList::<u8, 255, u8>::new();
+    for i in 0..255 {
+        l.try_push_front(i).unwrap();
+    }
+    assert_eq!(l.try_push_back(0), Err(0));
+    assert_eq!(l.len(), 255);
+    it::assert_equal(l.iter().rev().cloned(), 0..255);
+    while l.pop_back().is_some() { }
+    assert!(l.is_empty());
+}
+
+#[test]
+fn cursor()
+{
+    let mut l = ArrayList::<_, 8>::new();
+    for index in 0..5 {
+        l.try_push_back(index).unwrap();
+    }
+    {
+        let mut c = l.cursor();
+        assert_eq!(c.next(), Some(&mut 0));
+        assert_eq!(c.prev(), Some(&mut 0));
+        assert_eq!(c.prev(), None);
+        assert_eq!(c.prev(), Some(&mut 4));
+        c.try_insert(77).unwrap();
+        assert_eq!(c.next(), Some(&mut 77));
+        assert_eq!(c.next(), Some(&mut 4));
+        assert_eq!(c.current(), None);
+        c.try_insert(5).unwrap();
+        c.move_next();
+        c.move_next();
+        c.try_insert(-1).unwrap();
+        assert_eq!(c.try_insert(-2), Err(-2));
+    }
+    it::assert_equal(l.iter(), &[-1, 0, 1, 2, 3, 77, 4, 5]);
+    it::assert_equal(l.iter().rev(), &[5, 4, 77, 3, 2, 1, 0, -1]);
+
+    {
+        let mut c = l.cursor();
+        assert_eq!(c.remove(), Some(-1));
+        c.move_next();
+        assert_eq!(c.remove(), Some(1));
+        assert_eq!(c.current(), Some(&mut 2));
+        assert!(c.move_prev());
+        assert!(c.move_prev());
+        assert_eq!(c.remove(), None);
+        c.move_prev();
+        assert_eq!(c.remove(), Some(5));
+        assert_eq!(c.current(), None);
+    }
+    it::assert_equal(l.iter(), &[0, 2, 3, 77, 4]);
+    it::assert_equal(l.iter().rev(), &[4, 77, 3, 2, 0]);
+
+    let mut e = ArrayList::<i32, 8>::new();
+    let mut c = e.cursor();
+    assert!(!c.move_next());
+    assert!(!c.move_prev());
+}
+
+#[test]
+fn model()
+{
+    let mut l = ArrayList::<u32, 32, u8>::new();
+    let mut model = VecDeque::new();
+    // A simple linear congruential generator, for reproducible operations.
+    let mut seed = 1u32;
+    for _ in 0..2000 {
+        seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+        let op = (seed >> 16) % 5;
+        let value = seed >> 24;
+        match op {
+            0 => {
+                let full = model.len() == 32;
+                assert_eq!(l.try_push_back(value).is_err(), full);
+                if !full {
+                    model.push_back(value);
+                }
+            }
+            1 => {
+                let full = model.len() == 32;
+                assert_eq!(l.try_push_front(value).is_err(), full);
+                if !full {
+                    model.push_front(value);
+                }
+            }
+            2 => assert_eq!(l.pop_front(), model.pop_front()),
+            3 => assert_eq!(l.pop_back(), model.pop_back()),
+            _ => {
+                let at = value as usize % (model.len() + 1);
+                let mut c = l.cursor();
+                for _ in 0..at {
+                    c.move_next();
+                }
+                if at < model.len() && value & 1 == 0 {
+                    assert_eq!(c.remove(), model.remove(at));
+                } else if model.len() < 32 {
+                    c.try_insert(value).unwrap();
+                    model.insert(at, value);
+                }
+            }
+        }
+        assert_eq!(l.len(), model.len());
+        it::assert_equal(l.iter(), model.iter());
+        it::assert_equal(l.iter().rev(), model.iter().rev());
+    }
+}
+
+#[test]
+fn drop_elements()
+{
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl<'a> Drop for Counted<'a>
+    {
+        fn drop(&mut self) { self.0.set(self.0.get() + 1); }
+    }
+
+    let drops = Cell::new(0);
+    {
+        let mut l = ArrayList::<_, 6, u8>::new();
+        for _ in 0..6 {
+            l.try_push_back(Counted(&drops)).ok().unwrap();
+        }
+        assert!(l.try_push_back(Counted(&drops)).is_err());
+        assert_eq!(drops.get(), 1);
+        l.pop_front();
+        l.cursor().remove();
+        assert_eq!(drops.get(), 3);
+    }
+    assert_eq!(drops.get(), 7);
+}
@@ -0,0 +1,11 @@
+#!/bin/sh
+# Check that include/ixlist.h is what cbindgen generates from src/ffi.rs,
+# or regenerate it with --update.
+set -e
+
+cd "$(dirname "$0")/../.."
+if [ "$1" = --update ]; then
+    cbindgen --quiet --config cbindgen.toml --crate ixlist --output include/ixlist.h
+else
+    cbindgen --quiet --config cbindgen.toml --crate ixlist | diff -u include/ixlist.h -
+fi
@@ -0,0 +1,9 @@
+#!/bin/sh
+# Build the shared library, and build and run the C test program with it.
+set -e
+
+cd "$(dirname "$0")/../.."
+cargo rustc --release --features ffi --crate-type cdylib
+${CC:-cc} -std=c99 -Wall -Wextra -Werror -Iinclude -o target/release/test_ixlist \
+    tests/c/test_ixlist.c -Ltarget/release -lixlist
+LD_LIBRARY_PATH=target/release DYLD_LIBRARY_PATH=target/release target/release/test_ixlist
@@ -0,0 +1,134 @@
+/*
+ * Exercise the C interface through the shared library; see run.sh.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "ixlist.h"
+
+static int failures = 0;
+
+#define CHECK(cond) do { \
+        if (!(cond)) { \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+            failures++; \
+        } \
+    } while (0)
+
+/* Check that list holds the elements of expected, in both directions. */
+static void check_list(const IxList *list, int *const *expected, size_t len)
+{
+    void *elt;
+    size_t i = 0;
+    IxIter *iter = ixlist_iter_new(list);
+    CHECK(ixlist_len(list) == len);
+    while (ixlist_iter_next(iter, &elt)) {
+        CHECK(i < len && elt == expected[i]);
+        i++;
+    }
+    CHECK(i == len);
+    CHECK(!ixlist_iter_next(iter, &elt));
+    ixlist_iter_free(iter);
+
+    iter = ixlist_iter_new(list);
+    while (ixlist_iter_next_back(iter, &elt)) {
+        CHECK(i > 0 && elt == expected[i - 1]);
+        i--;
+    }
+    CHECK(i == 0);
+    ixlist_iter_free(iter);
+}
+
+int main(void)
+{
+    int values[6] = { 0, 1, 2, 3, 4, 5 };
+    int *v[6];
+    void *elt;
+    size_t i;
+    IxList *list;
+    IxCursor *c;
+
+    for (i = 0; i < 6; i++) {
+        v[i] = &values[i];
+    }
+
+    list = ixlist_new();
+    CHECK(ixlist_len(list) == 0);
+    CHECK(!ixlist_pop_front(list, &elt));
+    CHECK(ixlist_push_back(list, v[1]));
+    CHECK(ixlist_push_back(list, v[2]));
+    CHECK(ixlist_push_front(list, v[0]));
+    CHECK(ixlist_push_back(list, NULL));
+    {
+        int *expected[] = { v[0], v[1], v[2], NULL };
+        check_list(list, expected, 4);
+    }
+
+    /* A null element is still an element. */
+    elt = v[5];
+    CHECK(ixlist_pop_back(list, &elt));
+    CHECK(elt == NULL);
+    CHECK(ixlist_pop_front(list, NULL));
+
+    c = ixlist_cursor_new(list);
+    CHECK(ixlist_cursor_current(c, &elt) && elt == v[1]);
+    CHECK(ixlist_cursor_next(c, &elt) && elt == v[1]);
+    CHECK(ixlist_cursor_insert(c, v[3]));
+    CHECK(ixlist_cursor_current(c, &elt) && elt == v[3]);
+    CHECK(ixlist_cursor_next(c, NULL));
+    CHECK(ixlist_cursor_next(c, &elt) && elt == v[2]);
+    /* At the end now */
+    CHECK(!ixlist_cursor_current(c, &elt));
+    CHECK(!ixlist_cursor_remove(c, &elt));
+    CHECK(ixlist_cursor_insert(c, v[4]));
+    CHECK(ixlist_cursor_prev(c, &elt) && elt == v[2]);
+    CHECK(ixlist_cursor_prev(c, &elt) && elt == v[3]);
+    CHECK(ixlist_cursor_remove(c, &elt) && elt == v[3]);
+    CHECK(ixlist_cursor_current(c, &elt) && elt == v[2]);
+    CHECK(ixlist_cursor_prev(c, &elt) && elt == v[1]);
+    CHECK(!ixlist_cursor_prev(c, &elt));
+    CHECK(ixlist_cursor_prev(c, &elt) && elt == v[4]);
+    {
+        int *expected[] = { v[1], v[2], v[4] };
+        check_list(list, expected, 3);
+    }
+    ixlist_cursor_free(c);
+
+    /* Removing the element at a cursor's position invalidates the cursor. */
+    c = ixlist_cursor_new(list);
+    CHECK(ixlist_cursor_valid(c));
+    CHECK(ixlist_pop_back(list, &elt) && elt == v[4]);
+    CHECK(ixlist_cursor_valid(c));
+    CHECK(ixlist_pop_front(list, &elt) && elt == v[1]);
+    CHECK(!ixlist_cursor_valid(c));
+    CHECK(!ixlist_cursor_current(c, &elt));
+    CHECK(!ixlist_cursor_insert(c, v[0]));
+    CHECK(ixlist_push_front(list, v[1]));
+    CHECK(ixlist_push_back(list, v[4]));
+    ixlist_cursor_free(c);
+
+    for (i = 0; i < 1000; i++) {
+        CHECK(ixlist_push_front(list, v[i % 6]));
+    }
+    CHECK(ixlist_len(list) == 1003);
+    for (i = 0; i < 1000; i++) {
+        CHECK(ixlist_pop_front(list, &elt) && elt == v[(999 - i) % 6]);
+    }
+    {
+        int *expected[] = { v[1], v[2], v[4] };
+        check_list(list, expected, 3);
+    }
+
+    ixlist_free(list);
+    ixlist_free(NULL);
+    ixlist_cursor_free(NULL);
+    ixlist_iter_free(NULL);
+
+    if (failures) {
+        fprintf(stderr, "%d checks failed\n", failures);
+        return EXIT_FAILURE;
+    }
+    printf("ok\n");
+    return EXIT_SUCCESS;
+}
@@ -0,0 +1,230 @@
+#![cfg(feature = "std")]
+
+extern crate itertools as it;
+extern crate ixlist;
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ixlist::DiskList;
+
+/// A new, empty directory for a test.
+fn temp_dir() -> PathBuf
+{
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let n = COUNT.fetch_add(1, Ordering::SeqCst);
+    let dir = env::temp_dir().join(format!("ixlist-disk-{}-{}", process::id(), n));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn contents(l: &DiskList<u32>) -> Vec<u32>
+{
+    l.iter().cloned().collect()
+}
+
+/// Simulate a crash that cut the log off at **len** bytes.
+fn truncate_log(dir: &Path, len: u64)
+{
+    let log = OpenOptions::new().write(true).open(dir.join("log")).unwrap();
+    log.set_len(len).unwrap();
+}
+
+fn log_len(dir: &Path) -> u64
+{
+    fs::metadata(dir.join("log")).unwrap().len()
+}
+
+#[test]
+fn reopen()
+{
+    let dir = temp_dir();
+    {
+        let mut l = DiskList::open(&dir).unwrap();
+        assert!(l.is_empty());
+        for i in 0..5 {
+            l.push_back(i).unwrap();
+        }
+        l.push_front(10).unwrap();
+        assert_eq!(l.pop_front().unwrap(), Some(10));
+        assert_eq!(l.pop_back().unwrap(), Some(4));
+        let mut c = l.cursor();
+        c.move_next();
+        c.move_next();
+        c.insert(7).unwrap();
+        assert_eq!(c.current(), Some(&7));
+        c.move_next();
+        assert_eq!(c.remove().unwrap(), Some(2));
+        assert_eq!(c.current(), Some(&3));
+        c.move_prev();
+        c.move_prev();
+        c.move_prev();
+        c.move_prev();
+        assert_eq!(c.current(), None);
+        c.insert(8).unwrap();
+    }
+    let mut l = DiskList::<u32>::open(&dir).unwrap();
+    assert_eq!(contents(&l), [0, 1, 7, 3, 8]);
+    it::assert_equal(l.iter().rev(), &[8, 3, 7, 1, 0]);
+    assert_eq!(l.pop_front().unwrap(), Some(0));
+    drop(l);
+
+    let mut l = DiskList::<u32>::open(&dir).unwrap();
+    l.checkpoint().unwrap();
+    // Only the header is left in the log
+    assert_eq!(log_len(&dir), 16);
+    l.push_back(9).unwrap();
+    drop(l);
+    let mut l = DiskList::<u32>::open(&dir).unwrap();
+    assert_eq!(contents(&l), [1, 7, 3, 8, 9]);
+    while l.pop_front().unwrap().is_some() { }
+    assert_eq!(l.pop_back().unwrap(), None);
+    assert!(l.cursor().remove().unwrap().is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn crash_in_log()
+{
+    let dir = temp_dir();
+    // The state after each operation, and the log length it needs.
+    let mut states = vec![(0, vec![])];
+    let header_len;
+    {
+        let mut l = DiskList::open(&dir).unwrap();
+        header_len = log_len(&dir);
+        for i in 0..6 {
+            if i % 3 == 2 {
+                l.pop_front().unwrap();
+            } else {
+                l.push_back(i).unwrap();
+            }
+            states.push((log_len(&dir), contents(&l)));
+            {
+                let mut c = l.cursor();
+                c.move_next();
+                c.insert(100 + i).unwrap();
+            }
+            states.push((log_len(&dir), contents(&l)));
+        }
+    }
+    let full = log_len(&dir);
+    let saved = fs::read(dir.join("log")).unwrap();
+    for len in (0..full + 1).rev() {
+        fs::write(dir.join("log"), &saved).unwrap();
+        truncate_log(&dir, len);
+        let mut l = DiskList::<u32>::open(&dir).unwrap();
+        let &(good_len, ref state) = states.iter().rev().find(|s| s.0 <= len).unwrap();
+        assert_eq!(&contents(&l), state, "log cut at {}", len);
+        // The torn record is dropped, and the list keeps working.
+        assert_eq!(log_len(&dir), good_len.max(header_len));
+        l.push_back(1000).unwrap();
+        drop(l);
+        let l = DiskList::<u32>::open(&dir).unwrap();
+        assert_eq!(contents(&l).last(), Some(&1000));
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn crash_in_checkpoint()
+{
+    let dir = temp_dir();
+    let mut l = DiskList::open(&dir).unwrap();
+    for i in 0..4 {
+        l.push_front(i).unwrap();
+    }
+    let saved = fs::read(dir.join("log")).unwrap();
+    l.checkpoint().unwrap();
+    drop(l);
+    // Crash after the snapshot was written but before the log was emptied:
+    // the old records must not be applied twice.
+    fs::write(dir.join("log"), &saved).unwrap();
+    let mut l = DiskList::<u32>::open(&dir).unwrap();
+    assert_eq!(contents(&l), [3, 2, 1, 0]);
+    l.pop_back().unwrap();
+    drop(l);
+    let l = DiskList::<u32>::open(&dir).unwrap();
+    assert_eq!(contents(&l), [3, 2, 1]);
+
+    // Many operations, with automatic checkpoints in between.
+    let mut l = l;
+    for i in 0..3000 {
+        l.push_back(i).unwrap();
+        if i % 2 == 0 {
+            l.pop_front().unwrap();
+        }
+    }
+    let expected = contents(&l);
+    drop(l);
+    assert!(log_len(&dir) < 3000 * 8);
+    let l = DiskList::<u32>::open(&dir).unwrap();
+    assert_eq!(contents(&l), expected);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn damaged_snapshot()
+{
+    let dir = temp_dir();
+    let mut l = DiskList::open(&dir).unwrap();
+    l.push_back(1u32).unwrap();
+    l.push_back(2).unwrap();
+    l.checkpoint().unwrap();
+    drop(l);
+
+    let err = DiskList::<[u32; 4]>::open(&dir).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let mut snapshot = fs::read(dir.join("snapshot")).unwrap();
+    let last = snapshot.len() - 1;
+    snapshot[last] ^= 1;
+    fs::write(dir.join("snapshot"), &snapshot).unwrap();
+    let err = DiskList::<u32>::open(&dir).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn damaged_log()
+{
+    let dir = temp_dir();
+    let mut l = DiskList::open(&dir).unwrap();
+    for i in 0..3 {
+        l.push_back(i as u32).unwrap();
+    }
+    drop(l);
+    let saved = fs::read(dir.join("log")).unwrap();
+    let header_len = 16;
+    let rec_len = (saved.len() - header_len) / 3;
+
+    // Another element type
+    let err = DiskList::<u64>::open(&dir).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(fs::read(dir.join("log")).unwrap(), saved);
+
+    // A damaged record that is not at the end
+    let mut log = saved.clone();
+    log[header_len + rec_len + 12] ^= 1;
+    fs::write(dir.join("log"), &log).unwrap();
+    let err = DiskList::<u32>::open(&dir).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(fs::read(dir.join("log")).unwrap(), log);
+
+    // A missing record
+    let mut log = saved.clone();
+    log.drain(header_len + rec_len..header_len + 2 * rec_len);
+    fs::write(dir.join("log"), &log).unwrap();
+    let err = DiskList::<u32>::open(&dir).err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(fs::read(dir.join("log")).unwrap(), log);
+
+    fs::write(dir.join("log"), &saved).unwrap();
+    let l = DiskList::<u32>::open(&dir).unwrap();
+    assert_eq!(contents(&l), [0, 1, 2]);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "ffi")]
+
+extern crate ixlist;
+
+use std::ffi::c_void;
+use std::ptr;
+
+use ixlist::ffi::*;
+
+fn ptr_of(x: &mut i32) -> *mut c_void { x as *mut i32 as *mut c_void }
+
+#[test]
+fn list_cursor_iter()
+{
+    let mut values = [0, 1, 2, 3];
+    let [a, b, c, d] = &mut values;
+    let (a, b, c, d) = (ptr_of(a), ptr_of(b), ptr_of(c), ptr_of(d));
+    let mut out = ptr::null_mut();
+    unsafe {
+        let list = ixlist_new();
+        assert!(ixlist_push_back(list, b));
+        assert!(ixlist_push_front(list, a));
+        assert!(ixlist_push_back(list, c));
+        assert_eq!(ixlist_len(list), 3);
+
+        let cursor = ixlist_cursor_new(list);
+        assert!(ixlist_cursor_next(cursor, &mut out));
+        assert_eq!(out, a);
+        assert!(ixlist_cursor_insert(cursor, d));
+        assert!(ixlist_cursor_remove(cursor, ptr::null_mut()));
+        assert!(ixlist_cursor_current(cursor, &mut out));
+        assert_eq!(out, b);
+        // Popping another element keeps the cursor's position.
+        assert!(ixlist_pop_back(list, &mut out));
+        assert_eq!(out, c);
+        assert!(ixlist_cursor_valid(cursor));
+        assert!(ixlist_cursor_current(cursor, &mut out));
+        assert_eq!(out, b);
+        // Popping the element at the cursor invalidates it.
+        assert!(ixlist_pop_back(list, &mut out));
+        assert_eq!(out, b);
+        assert!(!ixlist_cursor_valid(cursor));
+        assert!(!ixlist_cursor_current(cursor, &mut out));
+        assert!(!ixlist_cursor_next(cursor, &mut out));
+        assert!(!ixlist_cursor_insert(cursor, d));
+        assert!(!ixlist_cursor_remove(cursor, &mut out));
+        assert_eq!(ixlist_len(list), 1);
+        ixlist_cursor_free(cursor);
+
+        let cursor = ixlist_cursor_new(list);
+        assert!(ixlist_cursor_next(cursor, ptr::null_mut()));
+        assert!(!ixlist_cursor_current(cursor, &mut out));
+        assert!(!ixlist_cursor_remove(cursor, &mut out));
+        assert!(ixlist_cursor_insert(cursor, d));
+        ixlist_cursor_free(cursor);
+
+        let iter = ixlist_iter_new(list);
+        let mut seen = Vec::new();
+        while ixlist_iter_next(iter, &mut out) {
+            seen.push(out);
+        }
+        assert!(!ixlist_iter_next_back(iter, &mut out));
+        ixlist_iter_free(iter);
+        assert_eq!(seen, [a, d]);
+
+        assert!(ixlist_pop_front(list, &mut out));
+        assert!(ixlist_pop_front(list, &mut out));
+        assert!(!ixlist_pop_front(list, &mut out));
+        assert_eq!(out, d);
+        ixlist_free(list);
+    }
+}
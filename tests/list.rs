@@ -152,3 +152,665 @@ fn from_iter()
     let l: List<_> = (0..5).collect();
     it::assert_equal(l.iter(), &[0, 1, 2, 3, 4]);
 }
+
+#[test]
+fn dedup()
+{
+    let mut l: List<_> = vec![1, 1, 2, 3, 3, 3, 1, 4, 4].into_iter().collect();
+    l.dedup();
+    it::assert_equal(l.iter(), &[1, 2, 3, 1, 4]);
+    it::assert_equal(l.iter().rev(), &[4, 1, 3, 2, 1]);
+
+    // fragment the storage before deduplicating
+    let mut l = List::new();
+    for i in 0..10 {
+        l.push_front(i / 3);
+        l.push_back(i / 3);
+    }
+    l.pop_front();
+    l.dedup_by_key(|x| *x / 2);
+    it::assert_equal(l.iter(), &[2, 1, 2]);
+    it::assert_equal(l.iter().rev(), &[2, 1, 2]);
+
+    let mut l: List<i32> = List::new();
+    l.dedup();
+    assert_eq!(l.len(), 0);
+}
+
+#[test]
+fn partition()
+{
+    let l: List<_> = (0..10).collect();
+    let (even, odd) = l.partition(|x| x % 2 == 0);
+    it::assert_equal(even.iter(), &[0, 2, 4, 6, 8]);
+    it::assert_equal(odd.iter().rev(), &[9, 7, 5, 3, 1]);
+
+    let mut l = List::new();
+    for i in 0..5 {
+        l.push_front(i);
+        l.push_back(i + 10);
+    }
+    assert_eq!(l.stable_partition(|x| x % 2 == 1), 4);
+    it::assert_equal(l.iter(), &[3, 1, 11, 13, 4, 2, 0, 10, 12, 14]);
+    it::assert_equal(l.iter().rev(), &[14, 12, 10, 0, 2, 4, 13, 11, 1, 3]);
+
+    assert_eq!(l.stable_partition(|_| true), 10);
+    assert_eq!(l.stable_partition(|_| false), 0);
+    it::assert_equal(l.iter(), &[3, 1, 11, 13, 4, 2, 0, 10, 12, 14]);
+    it::assert_equal(l.iter().rev(), &[14, 12, 10, 0, 2, 4, 13, 11, 1, 3]);
+    l.push_back(15);
+    it::assert_equal(l.iter().rev().take(2), &[15, 14]);
+}
+
+#[test]
+fn cursor_index()
+{
+    let mut l: List<_> = (0..10).collect();
+    let mut c = l.cursor();
+    assert_eq!(c.index(), Some(0));
+    c.next();
+    c.next();
+    assert_eq!(c.index(), Some(2));
+    c.prev();
+    assert_eq!(c.index(), Some(1));
+    c.insert(100);
+    assert_eq!(c.index(), Some(1));
+    assert_eq!(c.next(), Some(&mut 100));
+    c.seek(Seek::Tail);
+    assert_eq!(c.index(), None);
+    assert_eq!(c.prev(), Some(&mut 9));
+    assert_eq!(c.index(), Some(10));
+    c.seek(Seek::Head);
+    c.prev();
+    assert_eq!(c.index(), None);
+    c.next();
+    assert_eq!(c.index(), Some(0));
+
+    for &(seek, index, elt) in &[(Seek::To(5), Some(5), Some(4)),
+                                 (Seek::To(7), Some(7), Some(6)),
+                                 (Seek::To(1), Some(1), Some(100)),
+                                 (Seek::To(9), Some(9), Some(8)),
+                                 (Seek::To(11), None, None),
+                                 (Seek::To(100), None, None),
+                                 (Seek::FromTail(1), Some(10), Some(9)),
+                                 (Seek::FromTail(0), None, None),
+                                 (Seek::FromTail(11), Some(0), Some(0)),
+                                 (Seek::FromTail(100), Some(0), Some(0)),
+                                 (Seek::FromTail(3), Some(8), Some(7))]
+    {
+        c.seek(seek);
+        assert_eq!(c.index(), index);
+        let mut elt = elt;
+        assert_eq!(c.next(), elt.as_mut());
+        c.prev();
+    }
+}
+
+#[test]
+fn cursor_find()
+{
+    let mut l: List<_> = (0..10).collect();
+    {
+        let mut c = l.cursor();
+        assert_eq!(c.find_next(|x| x % 4 == 3), Some(&mut 3));
+        assert_eq!(c.index(), Some(4));
+        assert_eq!(c.find_next(|x| x % 4 == 3), Some(&mut 7));
+        assert_eq!(c.find_next(|x| x % 4 == 3), None);
+        assert_eq!(c.index(), None);
+        assert_eq!(c.find_next(|_| true), None);
+
+        assert_eq!(c.find_prev(|x| x % 4 == 1), Some(&mut 9));
+        assert_eq!(c.find_prev(|x| x % 4 == 1), Some(&mut 5));
+        assert_eq!(c.index(), Some(5));
+        c.insert(-5);
+        assert_eq!(c.find_prev(|x| x % 4 == 1), Some(&mut 1));
+        assert_eq!(c.find_prev(|x| x % 4 == 1), None);
+        assert_eq!(c.index(), Some(0));
+        assert_eq!(c.next(), Some(&mut 0));
+    }
+    it::assert_equal(l.iter(), &[0, 1, 2, 3, 4, -5, 5, 6, 7, 8, 9]);
+
+    {
+        let mut c = l.find(|&x| x > 4).unwrap();
+        assert_eq!(c.index(), Some(6));
+        assert_eq!(c.next(), Some(&mut 5));
+    }
+    {
+        let mut c = l.rfind(|&x| x < 4).unwrap();
+        assert_eq!(c.index(), Some(5));
+        assert_eq!(c.next(), Some(&mut -5));
+    }
+    assert!(l.find(|&x| x > 10).is_none());
+    assert!(l.rfind(|&x| x > 10).is_none());
+}
+
+#[test]
+fn cursor_edit()
+{
+    let mut l: List<_> = (0..5).collect();
+    {
+        let mut c = l.cursor();
+        c.insert_after(10);
+        assert_eq!(c.replace(-1), 0);
+        c.seek(Seek::Tail);
+        c.insert_after(20);
+        assert_eq!(c.index(), None);
+        c.prev();
+        c.insert_after(40);
+        assert_eq!(c.index(), Some(6));
+        assert_eq!(c.next(), Some(&mut 4));
+    }
+    it::assert_equal(l.iter(), &[20, -1, 10, 1, 2, 3, 4, 40]);
+    it::assert_equal(l.iter().rev(), &[40, 4, 3, 2, 1, 10, -1, 20]);
+
+    {
+        let mut c = l.cursor();
+        assert!(c.swap_with_next());
+        assert_eq!(c.index(), Some(1));
+        assert!(c.swap_with_next());
+        c.seek(Seek::FromTail(2));
+        assert!(c.swap_with_next());
+        assert!(!c.swap_with_next());
+        c.seek(Seek::Tail);
+        assert!(!c.swap_with_next());
+    }
+    it::assert_equal(l.iter(), &[-1, 10, 20, 1, 2, 3, 40, 4]);
+    it::assert_equal(l.iter().rev(), &[4, 40, 3, 2, 1, 20, 10, -1]);
+
+    {
+        let mut c = l.cursor();
+        c.seek(Seek::To(3));
+        c.insert_iter(vec![7, 8, 9]);
+        assert_eq!(c.index(), Some(3));
+        assert_eq!(c.next(), Some(&mut 7));
+        c.insert_iter(None);
+        assert_eq!(c.next(), Some(&mut 8));
+        c.seek(Seek::Head);
+        c.insert_iter(0..2);
+        c.seek(Seek::Tail);
+        c.insert_iter(5..7);
+        assert_eq!(c.next(), Some(&mut 5));
+    }
+    it::assert_equal(l.iter(), &[0, 1, -1, 10, 20, 7, 8, 9, 1, 2, 3, 40, 4, 5, 6]);
+    it::assert_equal(l.iter().rev(), &[6, 5, 4, 40, 3, 2, 1, 9, 8, 7, 20, 10, -1, 1, 0]);
+
+    let mut l = List::new();
+    l.cursor().insert_iter(0..3);
+    it::assert_equal(l.iter(), &[0, 1, 2]);
+    it::assert_equal(l.iter().rev(), &[2, 1, 0]);
+}
+
+#[test]
+#[should_panic]
+fn cursor_replace_end()
+{
+    let mut l: List<i32> = List::new();
+    l.cursor().replace(1);
+}
+
+#[test]
+fn cursor_ghost()
+{
+    let mut l: List<_> = (0..3).collect();
+    let mut c = l.cursor();
+    assert!(c.is_wrapping());
+    assert_eq!(c.current(), Some(&mut 0));
+    assert!(c.move_prev());
+    assert_eq!(c.current(), None);
+    assert!(c.move_prev());
+    assert_eq!(c.current(), Some(&mut 2));
+    assert!(c.move_next());
+    assert!(c.move_next());
+    assert_eq!(c.current(), Some(&mut 0));
+
+    c.set_wrapping(false);
+    assert!(!c.move_prev());
+    assert_eq!(c.prev(), None);
+    assert_eq!(c.current(), Some(&mut 0));
+    assert_eq!(c.seek(Seek::Forward(10)), 3);
+    assert_eq!(c.current(), None);
+    assert!(!c.move_next());
+    assert_eq!(c.next(), None);
+    assert_eq!(c.next(), None);
+    assert_eq!(c.prev(), Some(&mut 2));
+
+    assert_eq!(c.seek(Seek::Backward(10)), 2);
+    assert_eq!(c.seek(Seek::Backward(1)), 0);
+    assert_eq!(c.seek(Seek::Tail), 3);
+    assert_eq!(c.seek(Seek::To(1)), 2);
+    assert_eq!(c.seek(Seek::FromTail(1)), 1);
+    assert_eq!(c.seek(Seek::Head), 2);
+
+    let mut l: List<i32> = List::new();
+    let mut c = l.cursor();
+    assert_eq!(c.current(), None);
+    assert!(!c.move_next());
+    assert!(!c.move_prev());
+    assert_eq!(c.seek(Seek::Forward(1)), 0);
+}
+
+#[test]
+fn handles()
+{
+    let mut l: List<_> = (0..5).collect();
+    let (h1, h3) = {
+        let mut c = l.cursor();
+        c.seek(Seek::To(1));
+        let h1 = c.handle().unwrap();
+        c.seek(Seek::To(3));
+        let h3 = c.handle().unwrap();
+        c.seek(Seek::Tail);
+        assert_eq!(c.handle(), None);
+        (h1, h3)
+    };
+    assert_ne!(h1, h3);
+
+    // pops move the last nodes into the freed spots
+    l.pop_front();
+    l.pop_back();
+    l.push_front(-1);
+    l.dedup();
+    {
+        let mut c = l.cursor_at(h3).unwrap();
+        assert_eq!(c.current(), Some(&mut 3));
+        assert_eq!(c.index(), Some(3));
+        c.insert(30);
+        assert_eq!(c.next(), Some(&mut 30));
+        assert_eq!(c.index(), Some(4));
+    }
+    {
+        let mut c = l.cursor_at(h1).unwrap();
+        assert_eq!(c.index(), Some(1));
+        assert_eq!(c.next(), Some(&mut 1));
+        let h2 = c.handle().unwrap();
+        assert!(h2 != h1 && h2 != h3);
+        c.seek(Seek::Head);
+        assert_eq!(c.next(), Some(&mut -1));
+    }
+    l.linearize();
+    assert_eq!(l.cursor_at(h1).unwrap().current(), Some(&mut 1));
+    it::assert_equal(l.iter(), &[-1, 1, 2, 30, 3]);
+
+    // removing the element invalidates its handle, even if the slot is reused
+    l.pop_front();
+    l.pop_front();
+    assert!(l.cursor_at(h1).is_none());
+    l.push_back(5);
+    assert!(l.cursor_at(h1).is_none());
+    assert_eq!(l.cursor_at(h3).unwrap().current(), Some(&mut 3));
+}
+
+#[test]
+fn drain()
+{
+    // fragment the storage so that the removed runs are scattered
+    let mut l = List::new();
+    for i in 0..10 {
+        l.push_front(-i);
+        l.push_back(i);
+    }
+    l.pop_front();
+    l.pop_back();
+    let h = {
+        let mut c = l.cursor();
+        c.seek(Seek::FromTail(1));
+        c.handle().unwrap()
+    };
+
+    it::assert_equal(l.drain(2..6), vec![-6, -5, -4, -3]);
+    it::assert_equal(l.drain(..2).rev(), vec![-7, -8]);
+    it::assert_equal(l.iter(), &[-2, -1, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    it::assert_equal(l.iter().rev(), &[8, 7, 6, 5, 4, 3, 2, 1, 0, 0, -1, -2]);
+    assert_eq!(l.cursor_at(h).unwrap().current(), Some(&mut 8));
+    assert_eq!(l.drain(3..=4).len(), 2);
+    assert_eq!(l.drain(10..).len(), 0);
+
+    {
+        let mut c = l.cursor();
+        c.seek(Seek::To(4));
+        assert_eq!(c.remove_n(3), 3);
+        assert_eq!(c.index(), Some(4));
+        assert_eq!(c.current(), Some(&mut 6));
+        it::assert_equal(c.drain_forward(100), vec![6, 7, 8]);
+        assert_eq!(c.index(), None);
+        assert_eq!(c.remove_n(1), 0);
+        assert_eq!(c.prev(), Some(&mut 2));
+    }
+    it::assert_equal(l.iter(), &[-2, -1, 0, 2]);
+    it::assert_equal(l.iter().rev(), &[2, 0, -1, -2]);
+    assert!(l.cursor_at(h).is_none());
+
+    let (a, b) = {
+        let mut c = l.cursor();
+        c.next();
+        let a = c.handle().unwrap();
+        c.next();
+        (a, c.handle().unwrap())
+    };
+    assert!(l.drain_range(b, a).is_none());
+    it::assert_equal(l.drain_range(a, b).unwrap(), vec![-1, 0]);
+    assert!(l.drain_range(a, a).is_none());
+    it::assert_equal(l.iter(), &[-2, 2]);
+    it::assert_equal(l.iter().rev(), &[2, -2]);
+    l.push_back(3);
+    it::assert_equal(l.drain(..), vec![-2, 2, 3]);
+    assert_eq!(l.len(), 0);
+    l.push_back(4);
+    it::assert_equal(l.iter(), &[4]);
+}
+
+#[test]
+fn drain_both_ends()
+{
+    let mut l = List::new();
+    for i in 0..8 {
+        l.push_front(-i);
+        l.push_back(i);
+    }
+    let h = l.handles().nth(10).unwrap();
+    {
+        let mut d = l.drain(2..12);
+        assert_eq!(d.len(), 10);
+        assert_eq!(d.next(), Some(-5));
+        assert_eq!(d.next_back(), Some(3));
+        assert_eq!(d.next(), Some(-4));
+        assert_eq!(d.next_back(), Some(2));
+        assert_eq!(d.next_back(), Some(1));
+        assert_eq!(d.len(), 5);
+    }
+    it::assert_equal(l.iter(), &[-7, -6, 4, 5, 6, 7]);
+    it::assert_equal(l.iter().rev(), &[7, 6, 5, 4, -6, -7]);
+    assert!(l.cursor_at(h).is_none());
+    assert_eq!(l.len(), 6);
+    l.push_back(8);
+    it::assert_equal(l.drain(1..).rev(), vec![8, 7, 6, 5, 4, -6]);
+    it::assert_equal(l.iter(), &[-7]);
+}
+
+#[test]
+#[should_panic]
+fn drain_out_of_bounds()
+{
+    let mut l: List<_> = (0..3).collect();
+    l.drain(1..4);
+}
+
+#[test]
+fn sublist()
+{
+    let mut l: List<_> = (0..10).collect();
+    let handle_at = |l: &mut List<i32>, i| {
+        let mut c = l.cursor();
+        c.seek(Seek::To(i));
+        c.handle().unwrap()
+    };
+    let first = handle_at(&mut l, 3);
+    let last = handle_at(&mut l, 6);
+    l.pop_front();
+    l.push_front(-1);
+
+    assert!(l.range(last..=first).is_none());
+    let s = l.range(first..=last).unwrap();
+    assert_eq!(s.len(), 4);
+    it::assert_equal(s.iter(), &[3, 4, 5, 6]);
+    it::assert_equal(s.iter().rev(), &[6, 5, 4, 3]);
+    assert_eq!(s.iter().size_hint(), (4, Some(4)));
+    it::assert_equal(s, &[3, 4, 5, 6]);
+
+    let mut c = s.cursor();
+    assert_eq!(c.prev(), None);
+    assert_eq!(c.index(), None);
+    assert_eq!(c.prev(), Some(&6));
+    assert_eq!(c.index(), Some(3));
+    assert_eq!(c.next(), Some(&6));
+    assert_eq!(c.next(), None);
+    assert_eq!(c.next(), Some(&3));
+    assert_eq!(c.current(), Some(&4));
+    assert!(c.move_prev());
+    assert_eq!(c.index(), Some(0));
+
+    let one = l.range(first..=first).unwrap();
+    it::assert_equal(one, &[3]);
+    let mut c = one.cursor();
+    assert_eq!(c.next(), Some(&3));
+    assert_eq!(c.next(), None);
+    assert_eq!(c.prev(), None);
+    assert_eq!(c.prev(), Some(&3));
+}
+
+#[test]
+fn iter_handles()
+{
+    let mut l = List::new();
+    for i in 0..5 {
+        l.push_front(-i);
+        l.push_back(i);
+    }
+    l.pop_front();
+    let handles: Vec<_> = l.handles().collect();
+    assert_eq!(handles.len(), 9);
+    it::assert_equal(l.handles().rev(), handles.iter().rev().cloned());
+    for (&h, (h2, &x)) in handles.iter().zip(l.iter().with_handles()) {
+        assert_eq!(h, h2);
+        assert_eq!(l.iter_from(h).unwrap().next(), Some(&x));
+    }
+    for ((h, x), &h2) in l.iter_mut().with_handles().rev().zip(handles.iter().rev()) {
+        assert_eq!(h, h2);
+        *x *= 10;
+    }
+    it::assert_equal(l.iter(), &[-30, -20, -10, 0, 0, 10, 20, 30, 40]);
+
+    let h = handles[3];
+    l.pop_back();
+    l.pop_front();
+    l.push_front(-40);
+    let it = l.iter_from(h).unwrap();
+    assert_eq!(it.size_hint(), (5, Some(5)));
+    it::assert_equal(it, &[0, 0, 10, 20, 30]);
+    let it = l.iter_rev_from(h).unwrap();
+    assert_eq!(it.size_hint(), (4, Some(4)));
+    it::assert_equal(it, &[0, -10, -20, -40]);
+    it::assert_equal(l.iter_rev_from(h).unwrap().rev(), &[-40, -20, -10, 0]);
+
+    l.pop_front();
+    l.pop_front();
+    assert!(l.iter_from(handles[1]).is_none());
+    assert!(l.iter_rev_from(handles[1]).is_none());
+}
+
+#[test]
+fn values()
+{
+    let mut l = List::new();
+    for i in 0..5 {
+        l.push_front(i);
+        l.push_back(i + 10);
+    }
+    l.pop_front();
+    let mut v: Vec<_> = l.values().cloned().collect();
+    v.sort();
+    assert_eq!(v, [0, 1, 2, 3, 10, 11, 12, 13, 14]);
+    assert_eq!(l.values().len(), 9);
+    assert_eq!(l.values().rev().count(), 9);
+
+    for x in l.values_mut() {
+        *x *= 2;
+    }
+    let mut sum = 0;
+    l.for_each_unordered(|x| sum += x);
+    assert_eq!(sum, 2 * (6 + 60));
+    l.for_each_unordered_mut(|x| *x += 1);
+    it::assert_equal(l.iter(), &[7, 5, 3, 1, 21, 23, 25, 27, 29]);
+}
+
+#[test]
+fn iter_fast_paths()
+{
+    let mut l = List::new();
+    for i in 0..5 {
+        l.push_front(4 - i);
+        l.push_back(5 + i);
+    }
+    assert_eq!(l.iter().len(), 10);
+    assert_eq!(l.iter().count(), 10);
+    assert_eq!(l.iter().last(), Some(&9));
+    assert_eq!(l.iter_mut().last(), Some(&mut 9));
+    for n in 0..12 {
+        let mut iter = l.iter();
+        assert_eq!(iter.nth(n).cloned(), if n < 10 { Some(n) } else { None });
+        assert_eq!(iter.len(), 9 - n.min(9));
+        it::assert_equal(iter.cloned(), n + 1..10);
+
+        let mut iter = l.iter();
+        iter.next();
+        iter.next_back();
+        assert_eq!(iter.nth_back(n).cloned(), if n < 8 { Some(8 - n) } else { None });
+        it::assert_equal(iter.cloned(), 1..8usize.saturating_sub(n));
+    }
+    let mut iter = l.iter_mut();
+    assert_eq!(iter.nth(3), Some(&mut 3));
+    assert_eq!(iter.nth_back(3), Some(&mut 6));
+    assert_eq!(iter.len(), 2);
+    assert_eq!(iter.nth(2), None);
+    assert_eq!(iter.next(), None);
+    assert_eq!(iter.next_back(), None);
+
+    // Iter is Clone even if the element type is not
+    fn assert_clone<T: Clone>(_: &T) { }
+    let l: List<_> = (0..3).map(|i| vec![i]).collect();
+    assert_clone(&l.iter());
+}
+
+#[test]
+fn windows()
+{
+    let mut l = List::new();
+    for i in 0..3 {
+        l.push_front(2 - i);
+        l.push_back(3 + i);
+    }
+    assert_eq!(l.windows::<3>().len(), 4);
+    it::assert_equal(l.windows::<3>(),
+                     vec![[&0, &1, &2], [&1, &2, &3], [&2, &3, &4], [&3, &4, &5]]);
+    it::assert_equal(l.windows::<3>().rev(),
+                     vec![[&3, &4, &5], [&2, &3, &4], [&1, &2, &3], [&0, &1, &2]]);
+    it::assert_equal(l.windows::<1>().map(|[x]| x), l.iter());
+    it::assert_equal(l.windows::<6>(), vec![[&0, &1, &2, &3, &4, &5]]);
+    assert_eq!(l.windows::<7>().next(), None);
+
+    let diffs: Vec<_> = l.iter().pairs().map(|(a, b)| b - a).collect();
+    assert_eq!(diffs, [1; 5]);
+    let mut iter = l.iter();
+    iter.nth(2);
+    let mut pairs = iter.pairs();
+    assert_eq!(pairs.len(), 2);
+    assert_eq!(pairs.next_back(), Some((&4, &5)));
+    assert_eq!(pairs.next(), Some((&3, &4)));
+    assert_eq!(pairs.next(), None);
+}
+
+#[test]
+#[should_panic]
+fn windows_zero()
+{
+    let l: List<_> = (0..3).collect();
+    l.windows::<0>();
+}
+
+#[test]
+fn cycle()
+{
+    let mut l = List::new();
+    for i in 0..3 {
+        l.push_front(2 - i);
+        l.push_back(3 + i);
+    }
+    let h = l.handles().nth(4).unwrap();
+    let cycle = l.cycle_from(h).unwrap();
+    assert_eq!(cycle.len(), 6);
+    it::assert_equal(cycle, &[4, 5, 0, 1, 2, 3]);
+    it::assert_equal(l.cycle_from(h).unwrap().rev(), &[3, 2, 1, 0, 5, 4]);
+    let mut cycle = l.cycle_from(h).unwrap();
+    assert_eq!(cycle.next(), Some(&4));
+    assert_eq!(cycle.next_back(), Some(&3));
+    assert_eq!(cycle.next_back(), Some(&2));
+    it::assert_equal(cycle, &[5, 0, 1]);
+
+    it::assert_equal(l.cycle_forever_from(h).unwrap().take(14),
+                     &[4, 5, 0, 1, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5]);
+    it::assert_equal(l.cycle_forever_from(h).unwrap().rev().take(8),
+                     &[3, 2, 1, 0, 5, 4, 3, 2]);
+    let mut forever = l.cycle_forever_from(h).unwrap();
+    it::assert_equal(forever.by_ref().take(2), &[4, 5]);
+    it::assert_equal(forever.by_ref().rev().take(4), &[3, 2, 1, 0]);
+    it::assert_equal(forever.by_ref().take(3), &[0, 1, 2]);
+    it::assert_equal(forever.rev().take(2), &[5, 4]);
+
+    let first = l.handles().next().unwrap();
+    it::assert_equal(l.cycle_from(first).unwrap(), l.iter());
+    l.pop_front();
+    assert!(l.cycle_from(first).is_none());
+    assert!(l.cycle_forever_from(first).is_none());
+
+    let mut l = List::new();
+    l.push_back(1);
+    let h = l.handles().next().unwrap();
+    it::assert_equal(l.cycle_forever_from(h).unwrap().take(3), &[1, 1, 1]);
+}
+
+#[test]
+fn map()
+{
+    let mut l: List<String> = List::new();
+    for s in &["3", "2", "x", "1"] {
+        l.push_front(s.to_string());
+    }
+    l.pop_back();
+    let h = l.handles().nth(1).unwrap();
+    let lens = l.clone().map(|s| s.len() * 10);
+    it::assert_equal(lens.iter(), &[10, 10, 10]);
+    assert_eq!(lens.handles().position(|x| x == h), Some(1));
+    it::assert_equal(lens.iter_from(h).unwrap(), &[10, 10]);
+
+    let err = l.clone().try_map(|s| s.parse::<i32>());
+    assert!(err.is_err());
+    l.cursor_at(h).unwrap().replace("5".to_string());
+    let nums = l.try_map(|s| s.parse::<i32>()).unwrap();
+    it::assert_equal(nums.iter(), &[1, 5, 2]);
+    it::assert_equal(nums.iter_from(h).unwrap(), &[5, 2]);
+}
+
+#[test]
+fn clone()
+{
+    let mut l = List::new();
+    for i in 0..8 {
+        l.push_front(i);
+        l.push_back(-i);
+    }
+    l.drain(3..6);
+    l.pop_front();
+    let handles = l.handles().collect::<Vec<_>>();
+
+    let lin = l.clone_linearized();
+    it::assert_equal(lin.iter(), l.iter());
+    it::assert_equal(lin.values(), l.iter());
+    it::assert_equal(lin.handles(), handles.iter().cloned());
+    it::assert_equal(lin.iter().rev(), l.iter().rev());
+
+    let mut copy = List::with_capacity(32);
+    copy.push_back(100);
+    let cap = copy.capacity();
+    copy.clone_from(&l);
+    assert_eq!(copy.capacity(), cap);
+    it::assert_equal(copy.iter(), l.iter());
+    it::assert_equal(copy.values(), l.values());
+    it::assert_equal(copy.handles(), handles.iter().cloned());
+
+    let empty = List::<i32>::new();
+    assert!(empty.clone_linearized().is_empty());
+    copy.clone_from(&empty);
+    assert!(copy.is_empty());
+    assert_eq!(copy.capacity(), cap);
+    assert_eq!(copy.iter().next(), None);
+}
@@ -0,0 +1,174 @@
+extern crate itertools as it;
+extern crate ixlist;
+
+use std::mem::size_of;
+use std::slice;
+
+use ixlist::{ArchivedList, List, RawList};
+use ixlist::raw::RawError;
+
+/// Return an aligned, zeroed buffer of **len** bytes.
+fn buffer(len: usize) -> Vec<u64>
+{
+    vec![0; len.div_ceil(8)]
+}
+
+fn bytes(mem: &mut [u64]) -> &mut [u8]
+{
+    unsafe { slice::from_raw_parts_mut(mem.as_mut_ptr() as *mut u8, mem.len() * 8) }
+}
+
+#[test]
+fn push_pop()
+{
+    let size = RawList::<i32>::size_for(4);
+    let mut mem = buffer(size);
+    let mut l = RawList::<i32>::init(&mut bytes(&mut mem)[..size]).unwrap();
+    assert_eq!(l.capacity(), 4);
+    assert_eq!(l.pop_back(), None);
+    l.push_back(1).unwrap();
+    l.push_front(0).unwrap();
+    l.push_back(2).unwrap();
+    l.push_front(-1).unwrap();
+    assert_eq!(l.push_back(3), Err(3));
+    it::assert_equal(l.iter(), &[-1, 0, 1, 2]);
+    it::assert_equal(l.iter().rev(), &[2, 1, 0, -1]);
+    assert_eq!(l.pop_front(), Some(-1));
+    assert_eq!(l.pop_back(), Some(2));
+    l.push_back(5).unwrap();
+    it::assert_equal(l.iter(), &[0, 1, 5]);
+    assert_eq!(format!("{:?}", l), "[0, 1, 5]");
+    l.clear();
+    assert!(l.is_empty());
+    assert_eq!(l.iter().next(), None);
+}
+
+#[test]
+fn attach()
+{
+    let mut mem = buffer(512);
+    {
+        let mut l = RawList::<[u16; 3], u64>::init(bytes(&mut mem)).unwrap();
+        for i in 0..6 {
+            l.push_back([i, i + 1, i + 2]).unwrap();
+            l.push_front([i; 3]).unwrap();
+        }
+        l.pop_front();
+    }
+    // Another process would see a copy of the bytes.
+    let mut copy = mem.clone();
+    let l = RawList::<[u16; 3], u64>::attach(bytes(&mut copy)).unwrap();
+    assert_eq!(l.len(), 11);
+    it::assert_equal(l.iter().map(|x| x[0]), vec![4, 3, 2, 1, 0, 0, 1, 2, 3, 4, 5]);
+
+    assert_eq!(RawList::<[u16; 3], u32>::attach(bytes(&mut copy)).unwrap_err(),
+               RawError::BadMagic);
+    assert_eq!(RawList::<[u64; 2], u64>::attach(bytes(&mut copy)).unwrap_err(),
+               RawError::NodeSize);
+    assert_eq!(RawList::<[u16; 3], u64>::attach(&mut bytes(&mut copy)[..64]).unwrap_err(),
+               RawError::TooSmall);
+    assert_eq!(RawList::<[u16; 3], u64>::attach(&mut bytes(&mut copy)[1..]).unwrap_err(),
+               RawError::Misaligned);
+}
+
+#[test]
+fn attach_corrupt()
+{
+    let mut mem = buffer(RawList::<u32>::size_for(8));
+    {
+        let mut l = RawList::<u32>::init(bytes(&mut mem)).unwrap();
+        for i in 0..5 {
+            l.push_back(i).unwrap();
+        }
+    }
+    let header = 5 * size_of::<u32>();
+    let node = 3 * size_of::<u32>();
+    // Header: magic, node size, head, tail, len. Node: prev, next, value.
+    let corruptions: &[(usize, u32)] = &[
+        (16, 6),                  // len
+        (16, u32::MAX),           // len
+        (8, 1),                   // head
+        (12, 3),                  // tail
+        (header + node * 2 + 4, 2), // next link to itself
+        (header + node * 2 + 4, 0), // next link to the first node
+        (header + node * 4 + 4, 0), // tail's next link is not null
+        (header + node, 1),       // prev link
+    ];
+    for &(offset, value) in corruptions {
+        let mut copy = mem.clone();
+        bytes(&mut copy)[offset..offset + 4].copy_from_slice(&value.to_ne_bytes());
+        assert_eq!(RawList::<u32>::attach(bytes(&mut copy)).unwrap_err(), RawError::Corrupt,
+                   "corruption at {}", offset);
+    }
+    let l = RawList::<u32>::attach(bytes(&mut mem)).unwrap();
+    it::assert_equal(l.iter(), &[0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn archive()
+{
+    let mut list = List::new();
+    list.extend(0..10u32);
+    list.push_front(100);
+    let h = list.handles().nth(4).unwrap();
+    list.cursor_at(h).unwrap().remove_n(3);
+    list.pop_back();
+
+    let size = ArchivedList::<u32, u64>::size_for(list.len());
+    let mut mem = buffer(size);
+    assert_eq!(ArchivedList::<u32, u64>::write(&list, &mut bytes(&mut mem)[..size - 1]),
+               Err(RawError::TooSmall));
+    // Nothing is written to a buffer that is too small.
+    assert!(bytes(&mut mem).iter().all(|&b| b == 0));
+    assert_eq!(ArchivedList::<u32, u64>::write(&list, bytes(&mut mem)), Ok(size));
+
+    let file: &[u8] = &bytes(&mut mem)[..size];
+    let archive = ArchivedList::<u32, u64>::from_bytes(file).unwrap();
+    assert_eq!(archive.len(), list.len());
+    it::assert_equal(archive.iter(), list.iter());
+    it::assert_equal(archive.iter().rev(), list.iter().rev());
+    it::assert_equal(archive, &[100, 0, 1, 2, 6, 7, 8]);
+    assert_eq!(archive.iter().len(), 7);
+    let mut iter = archive.iter();
+    assert_eq!(iter.next(), Some(&100));
+    assert_eq!(iter.next_back(), Some(&8));
+    assert_eq!(iter.len(), 5);
+    assert_eq!(format!("{:?}", archive), "[100, 0, 1, 2, 6, 7, 8]");
+
+    // An archive is a RawList too.
+    let l = RawList::<u32, u64>::attach(&mut bytes(&mut mem)[..size]).unwrap();
+    it::assert_equal(l.iter(), list.iter());
+
+    let empty = List::new();
+    assert_eq!(ArchivedList::<u32>::write(&empty, bytes(&mut mem)), Ok(ArchivedList::<u32>::size_for(0)));
+    assert!(ArchivedList::<u32>::from_bytes(bytes(&mut mem)).unwrap().is_empty());
+}
+
+#[test]
+fn archive_corrupt()
+{
+    let list = (0..4u32).collect::<List<_>>();
+    let size = ArchivedList::<u32>::size_for(list.len());
+    let mut mem = buffer(size + 8);
+    ArchivedList::<u32>::write(&list, bytes(&mut mem)).unwrap();
+    let file: &[u8] = &bytes(&mut mem)[..size + 8];
+    assert!(ArchivedList::<u32>::from_bytes(file).is_ok());
+    assert_eq!(ArchivedList::<u32>::from_bytes(&file[..size - 1]).err(), Some(RawError::TooSmall));
+    assert_eq!(ArchivedList::<u32>::from_bytes(&file[1..size + 1]).err(), Some(RawError::Misaligned));
+    assert_eq!(ArchivedList::<u32, u64>::from_bytes(file).err(), Some(RawError::BadMagic));
+    assert_eq!(ArchivedList::<[u32; 2]>::from_bytes(file).err(), Some(RawError::NodeSize));
+
+    // Point the last node's next link back to the first.
+    let last = bytes(&mut mem)[size - 8..size - 4].to_vec();
+    assert_eq!(last, u32::MAX.to_ne_bytes());
+    bytes(&mut mem)[size - 8..size - 4].copy_from_slice(&0u32.to_ne_bytes());
+    let file: &[u8] = &bytes(&mut mem)[..size];
+    assert_eq!(ArchivedList::<u32>::from_bytes(file).err(), Some(RawError::Corrupt));
+}
+
+#[test]
+#[should_panic]
+fn size_for_overflow()
+{
+    RawList::<u32>::size_for(usize::MAX / 4);
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "serde")]
+
+extern crate itertools as it;
+extern crate ixlist;
+extern crate serde_json;
+
+use ixlist::{List, ListLayout};
+use ixlist::storage::InlineStorage;
+
+/// A list whose node order differs from its element order, with a free slot.
+fn shuffled() -> List<i32>
+{
+    let mut l = List::new();
+    l.extend(0..4);
+    l.push_front(-1);
+    l.push_front(-2);
+    let h = l.handles().nth(3).unwrap();
+    l.cursor_at(h).unwrap().remove_n(1);
+    l
+}
+
+#[test]
+fn sequence()
+{
+    let l = shuffled();
+    let json = serde_json::to_string(&l).unwrap();
+    assert_eq!(json, "[-2,-1,0,2,3]");
+    let m: List<i32> = serde_json::from_str(&json).unwrap();
+    it::assert_equal(m.iter(), l.iter());
+    let m: List<i32, InlineStorage<_, 2>> = serde_json::from_str(&json).unwrap();
+    it::assert_equal(m.iter().rev(), l.iter().rev());
+    assert!(serde_json::from_str::<List<i32>>("{}").is_err());
+}
+
+#[test]
+fn layout()
+{
+    let l = shuffled();
+    let handles = l.handles().collect::<Vec<_>>();
+    let json = serde_json::to_string(&l.serialize_layout()).unwrap();
+    let ListLayout(mut m): ListLayout<List<i32>> = serde_json::from_str(&json).unwrap();
+    it::assert_equal(m.iter(), l.iter());
+    it::assert_equal(m.iter().rev(), l.iter().rev());
+    it::assert_equal(m.handles(), handles.iter().cloned());
+    m.cursor_at(handles[2]).unwrap().insert(10);
+    m.push_back(4);
+    it::assert_equal(m.iter(), &[-2, -1, 10, 0, 2, 3, 4]);
+    assert_eq!(json, serde_json::to_string(&ListLayout(l)).unwrap());
+
+    let empty = serde_json::to_string(&List::<i32>::new().serialize_layout()).unwrap();
+    assert_eq!(empty, r#"{"link":[null,null],"nodes":[],"slots":[],"free":null}"#);
+    let ListLayout(m): ListLayout<List<i32>> = serde_json::from_str(&empty).unwrap();
+    assert!(m.is_empty());
+}
+
+#[test]
+fn layout_corrupt()
+{
+    let valid = r#"{"link":[1,0],"nodes":[[1,null,0,20],[null,0,1,10]],"slots":[[0,0],[1,0],[null,3]],"free":2}"#;
+    let ListLayout(l): ListLayout<List<i32>> = serde_json::from_str(valid).unwrap();
+    it::assert_equal(l.iter(), &[10, 20]);
+
+    let corrupt = [
+        // Wrong tail
+        r#"{"link":[1,1],"nodes":[[1,null,0,20],[null,0,1,10]],"slots":[[0,0],[1,0],[null,3]],"free":2}"#,
+        // Prev link does not point back
+        r#"{"link":[1,0],"nodes":[[null,null,0,20],[null,0,1,10]],"slots":[[0,0],[1,0],[null,3]],"free":2}"#,
+        // Cycle
+        r#"{"link":[1,0],"nodes":[[1,1,0,20],[0,0,1,10]],"slots":[[0,0],[1,0],[null,3]],"free":2}"#,
+        // Link out of bounds
+        r#"{"link":[1,0],"nodes":[[1,5,0,20],[null,0,1,10]],"slots":[[0,0],[1,0],[null,3]],"free":2}"#,
+        // Two nodes with the same slot
+        r#"{"link":[1,0],"nodes":[[1,null,0,20],[null,0,0,10]],"slots":[[0,0],[1,0],[null,3]],"free":2}"#,
+        // Slot out of bounds
+        r#"{"link":[1,0],"nodes":[[1,null,7,20],[null,0,1,10]],"slots":[[0,0],[1,0],[null,3]],"free":2}"#,
+        // Free slot missing from the free list
+        r#"{"link":[1,0],"nodes":[[1,null,0,20],[null,0,1,10]],"slots":[[0,0],[1,0],[null,3]],"free":null}"#,
+        // Used slot on the free list
+        r#"{"link":[1,0],"nodes":[[1,null,0,20],[null,0,1,10]],"slots":[[0,0],[1,0],[0,3]],"free":2}"#,
+        // Free list cycle
+        r#"{"link":[1,0],"nodes":[[1,null,0,20],[null,0,1,10]],"slots":[[0,0],[1,0],[2,3]],"free":2}"#,
+        // Missing field
+        r#"{"link":[1,0],"nodes":[[1,null,0,20],[null,0,1,10]],"slots":[[0,0],[1,0],[null,3]]}"#,
+    ];
+    for input in &corrupt {
+        let res = serde_json::from_str::<ListLayout<List<i32>>>(input);
+        assert!(res.is_err(), "accepted {}", input);
+    }
+}
@@ -0,0 +1,283 @@
+extern crate itertools as it;
+extern crate ixlist;
+
+use std::alloc::Layout;
+use std::cell::Cell;
+use std::mem::MaybeUninit;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr::NonNull;
+
+use ixlist::{
+    List,
+    Node,
+    Seek,
+    Slot,
+    Storage,
+    TryReserveError,
+};
+use ixlist::storage::{
+    Alloc,
+    AllocError,
+    AllocStorage,
+    Global,
+    InlineStorage,
+    SliceStorage,
+};
+
+/// Run the same operations on lists with any storage.
+fn exercise<S, L>(mut l: List<i32, S, L>)
+    where S: Storage<Node<i32>>,
+          L: Storage<Slot>,
+{
+    for i in 0..4 {
+        l.push_back(i);
+        l.push_front(-i);
+    }
+    it::assert_equal(l.iter(), &[-3, -2, -1, 0, 0, 1, 2, 3]);
+    let h = l.handles().nth(2).unwrap();
+    assert_eq!(l.pop_front(), Some(-3));
+    {
+        let mut c = l.cursor_at(h).unwrap();
+        c.insert(10);
+        c.seek(Seek::Forward(3));
+        c.insert_after(20);
+        assert_eq!(c.remove_n(2), 2);
+    }
+    it::assert_equal(l.iter(), &[-2, 10, -1, 0, 1, 2, 3]);
+    it::assert_equal(l.drain(1..3), vec![10, -1]);
+    l.linearize();
+    it::assert_equal(l.values(), &[-2, 0, 1, 2, 3]);
+    it::assert_equal(l.iter().rev(), &[3, 2, 1, 0, -2]);
+    l.dedup_by_key(|x| *x / 2);
+    it::assert_equal(l.iter(), &[-2, 0, 2]);
+}
+
+#[test]
+fn inline_storage()
+{
+    let l = List::<_, InlineStorage<_, 4>>::default();
+    exercise(l);
+    exercise(List::<_, InlineStorage<_, 4>, InlineStorage<_, 4>>::default());
+
+    let mut l: List<i32, InlineStorage<_, 4>> = (0..4).collect();
+    assert!(!l.clone().partition(|x| *x < 2).0.is_empty());
+    l.push_back(4);
+    it::assert_equal(l.iter(), &[0, 1, 2, 3, 4]);
+    let copy = l.clone_linearized();
+    it::assert_equal(copy.iter(), l.iter());
+}
+
+#[test]
+fn inline_storage_spill()
+{
+    let mut s = InlineStorage::<String, 2>::new();
+    assert_eq!(s.capacity(), 2);
+    s.push("a".to_string()).unwrap();
+    s.push("b".to_string()).unwrap();
+    assert!(!s.spilled());
+    s.push("c".to_string()).unwrap();
+    assert!(s.spilled());
+    assert_eq!(s.swap_remove(0), "a");
+    assert_eq!(&s[..], &["c", "b"]);
+}
+
+#[test]
+fn inline_storage_overflow()
+{
+    let mut s = InlineStorage::<u8, 2>::new();
+    s.push(1).unwrap();
+    let err = s.try_reserve(usize::MAX).unwrap_err();
+    assert_eq!(err.to_string(), "capacity overflow");
+    assert!(!s.spilled());
+
+    let mut l = List::<_, InlineStorage<_, 4>, InlineStorage<_, 4>>::default();
+    l.extend(0..2);
+    assert!(l.try_reserve(usize::MAX).is_err());
+    it::assert_equal(l.iter(), &[0, 1]);
+}
+
+#[test]
+fn slice_storage()
+{
+    let mut buf: [MaybeUninit<Node<i32>>; 16] = unsafe { MaybeUninit::uninit().assume_init() };
+    exercise(List::with_storage(SliceStorage::new(&mut buf)));
+
+    let mut buf: [MaybeUninit<Node<i32>>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+    let mut l = List::with_storage(SliceStorage::new(&mut buf));
+    l.push_back(1);
+    l.push_front(0);
+    it::assert_equal(l.iter(), &[0, 1]);
+
+    let mut nodes: [MaybeUninit<Node<i32>>; 16] = unsafe { MaybeUninit::uninit().assume_init() };
+    let mut slots: [MaybeUninit<Slot>; 16] = unsafe { MaybeUninit::uninit().assume_init() };
+    exercise(List::with_storages(SliceStorage::new(&mut nodes), SliceStorage::new(&mut slots)));
+
+    // A free slot is reused even when the slot storage is full.
+    let mut nodes: [MaybeUninit<Node<i32>>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+    let mut slots: [MaybeUninit<Slot>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+    let mut l = List::with_storages(SliceStorage::new(&mut nodes), SliceStorage::new(&mut slots));
+    l.try_extend(0..2).unwrap();
+    assert_eq!(l.try_push_back(2).unwrap_err().error(), &TryReserveError::full());
+    l.pop_front();
+    l.try_reserve(1).unwrap();
+    l.try_push_back(2).unwrap();
+    it::assert_equal(l.iter(), &[1, 2]);
+}
+
+#[test]
+#[should_panic]
+fn slice_storage_full()
+{
+    let mut buf: [MaybeUninit<Node<i32>>; 2] = unsafe { MaybeUninit::uninit().assume_init() };
+    let mut l = List::with_storage(SliceStorage::new(&mut buf));
+    l.extend(0..3);
+}
+
+#[test]
+fn slice_storage_full_unchanged()
+{
+    let mut buf: [MaybeUninit<Node<i32>>; 3] = unsafe { MaybeUninit::uninit().assume_init() };
+    let mut l = List::with_storage(SliceStorage::new(&mut buf));
+    l.push_back(0);
+    let h = l.handles().next().unwrap();
+    let res = panic::catch_unwind(AssertUnwindSafe(|| l.extend(1..5)));
+    assert!(res.is_err());
+    it::assert_equal(l.iter(), &[0]);
+    assert_eq!(l.handles().next(), Some(h));
+    l.extend(1..3);
+    it::assert_equal(l.iter(), &[0, 1, 2]);
+}
+
+#[test]
+fn try_push()
+{
+    let mut buf: [MaybeUninit<Node<i32>>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+    let mut l = List::with_storage(SliceStorage::new(&mut buf));
+    l.try_push_back(1).unwrap();
+    l.try_push_front(0).unwrap();
+    l.try_extend(2..4).unwrap();
+    let handles = l.handles().collect::<Vec<_>>();
+
+    let err = l.try_push_back(4).unwrap_err();
+    assert_eq!(err.error(), &TryReserveError::full());
+    assert_eq!(err.into_value(), 4);
+    assert_eq!(l.try_push_front(-1).unwrap_err().into_value(), -1);
+    assert_eq!(l.cursor().try_insert(5).unwrap_err().into_value(), 5);
+    assert!(l.try_reserve(1).is_err());
+    it::assert_equal(l.iter(), &[0, 1, 2, 3]);
+
+    // Failing in the middle of try_extend leaves the list as it was, and
+    // hands back every element that was not inserted.
+    l.pop_front();
+    l.pop_back();
+    let err = l.try_extend((10..14).filter(|_| true)).unwrap_err();
+    assert_eq!(err.error(), &TryReserveError::full());
+    it::assert_equal(err.into_values(), 10..14);
+    it::assert_equal(l.iter(), &[1, 2]);
+    it::assert_equal(l.iter().rev(), &[2, 1]);
+    it::assert_equal(l.handles(), handles[1..3].iter().cloned());
+    l.try_extend(10..12).unwrap();
+    it::assert_equal(l.iter(), &[1, 2, 10, 11]);
+    assert!(l.iter_from(handles[0]).is_none());
+    assert!(l.iter_from(handles[3]).is_none());
+
+    {
+        let mut c = l.cursor();
+        c.seek(Seek::Forward(1));
+        assert!(c.try_insert(7).is_err());
+        assert_eq!(c.current(), Some(&mut 2));
+    }
+    l.pop_back();
+    {
+        let mut c = l.cursor();
+        c.seek(Seek::Forward(1));
+        c.try_insert(7).unwrap();
+        assert_eq!(c.current(), Some(&mut 7));
+    }
+    it::assert_equal(l.iter(), &[1, 7, 2, 10]);
+}
+
+#[test]
+fn try_reserve()
+{
+    let mut l = List::new();
+    l.push_back(1);
+    let err = l.try_reserve(usize::MAX).unwrap_err();
+    assert!(err != TryReserveError::full());
+    assert!(!err.to_string().is_empty());
+    l.try_reserve(10).unwrap();
+    l.try_extend(0..10).unwrap();
+    assert_eq!(l.len(), 11);
+
+    let mut l = List::<_, InlineStorage<_, 2>>::default();
+    l.try_reserve(2).unwrap();
+    l.try_extend(0..3).unwrap();
+    it::assert_equal(l.iter(), &[0, 1, 2]);
+}
+
+/// An allocator that counts its live blocks, and fails when there are
+/// too many.
+struct Counting
+{
+    live: Cell<usize>,
+    limit: usize,
+}
+
+unsafe impl Alloc for Counting
+{
+    fn allocate(&self, layout: Layout) -> Result<NonNull<u8>, AllocError>
+    {
+        if self.live.get() == self.limit {
+            return Err(AllocError);
+        }
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout)
+    {
+        self.live.set(self.live.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn alloc_storage()
+{
+    exercise(List::<_, AllocStorage<_>>::default());
+    exercise(List::with_alloc(Global));
+
+    let arena = Counting { live: Cell::new(0), limit: 4 };
+    {
+        let mut l = List::with_alloc(&arena);
+        assert_eq!(arena.live.get(), 0);
+        l.extend(0..3);
+        // One block for the nodes, and one for the slot table.
+        assert_eq!(arena.live.get(), 2);
+        let copy = l.clone();
+        assert_eq!(arena.live.get(), 4);
+        // The next reallocation needs a fifth block at once.
+        it::assert_equal(l.try_extend(3..10).unwrap_err().into_values(), 3..10);
+        it::assert_equal(l.iter(), &[0, 1, 2]);
+        l.try_extend(3..4).unwrap();
+        it::assert_equal(l.iter(), &[0, 1, 2, 3]);
+        drop(copy);
+        l.extend(4..10);
+        it::assert_equal(l.iter().rev(), &[9, 8, 7, 6, 5, 4, 3, 2, 1, 0]);
+        assert_eq!(arena.live.get(), 2);
+    }
+    assert_eq!(arena.live.get(), 0);
+}
+
+#[cfg(feature = "allocator_api")]
+#[test]
+fn allocator_api()
+{
+    use std::alloc::System;
+
+    let mut l = List::new_in(System);
+    l.extend(0..4);
+    l.push_front(-1);
+    it::assert_equal(l.iter(), &[-1, 0, 1, 2, 3]);
+    exercise(List::new_in(System));
+}